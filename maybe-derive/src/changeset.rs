@@ -0,0 +1,181 @@
+use crate::{
+    options::named_fields,
+    ty::{maybe_inner, omittable_inner},
+};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    meta::ParseNestedMeta, parenthesized, punctuated::Punctuated, token, Attribute, DeriveInput,
+    Expr, Ident, Path, Result, Token,
+};
+
+/// Container keys read by diesel's other derives, which don't affect changesets.
+const OTHER_CONTAINER_KEYS: &[&str] = &[
+    "aggregate",
+    "not_sized",
+    "foreign_derive",
+    "sql_type",
+    "treat_none_as_default_value",
+    "belongs_to",
+    "mysql_type",
+    "sqlite_type",
+    "postgres_type",
+    "check_for_backend",
+    "base_query",
+    "base_query_type",
+];
+
+/// Field keys read by diesel's other derives, which don't affect changesets.
+const OTHER_FIELD_KEYS: &[&str] = &[
+    "skip_insertion",
+    "sql_type",
+    "treat_none_as_default_value",
+    "deserialize_as",
+    "select_expression",
+    "select_expression_type",
+];
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let mut table_name = None;
+    let mut primary_key = None;
+    parse_diesel_attrs(&input.attrs, OTHER_CONTAINER_KEYS, |meta| {
+        if meta.path.is_ident("table_name") {
+            table_name = Some(meta.value()?.parse::<Path>()?);
+            Ok(true)
+        } else if meta.path.is_ident("primary_key") {
+            let content;
+            parenthesized!(content in meta.input);
+            primary_key = Some(Punctuated::<Ident, Token![,]>::parse_terminated(&content)?);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    })?;
+    let primary_key = match primary_key {
+        Some(primary_key) => primary_key.into_iter().collect(),
+        None => vec![Ident::new("id", Span::call_site())],
+    };
+    let table_name = match table_name {
+        Some(table_name) => table_name,
+        None => Ident::new(&default_table_name(&input.ident), Span::call_site()).into(),
+    };
+    let fields = named_fields(&input, "AsChangeset")?;
+
+    let diesel = quote!(::maybe::__private::diesel);
+    let mut changeset_tys = Vec::new();
+    let mut changesets = Vec::new();
+    let mut has_primary_key = false;
+    for field in fields {
+        let mut column_name = None;
+        let mut skip = false;
+        parse_diesel_attrs(&field.attrs, OTHER_FIELD_KEYS, |meta| {
+            if meta.path.is_ident("column_name") {
+                column_name = Some(meta.value()?.parse::<Ident>()?);
+                Ok(true)
+            } else if meta.path.is_ident("skip_update") {
+                skip = true;
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        })?;
+        let ident = field.ident.as_ref().expect("named field");
+        let column = column_name.as_ref().unwrap_or(ident);
+        if primary_key.contains(column) {
+            has_primary_key = true;
+            continue;
+        }
+        if skip {
+            continue;
+        }
+        let column = quote!(#table_name::#column);
+        let ty = &field.ty;
+        if let Some(inner) = maybe_inner(ty) {
+            changeset_tys.push(quote! {
+                ::core::option::Option<
+                    #diesel::dsl::Eq<#column, ::core::option::Option<#inner>>
+                >
+            });
+            changesets.push(quote!(::maybe::Maybe::assign(self.#ident, #column)));
+        } else if let Some(inner) = omittable_inner(ty) {
+            changeset_tys.push(quote! {
+                ::core::option::Option<#diesel::dsl::Eq<#column, #inner>>
+            });
+            changesets.push(quote!(::maybe::Omittable::assign(self.#ident, #column)));
+        } else {
+            changeset_tys.push(quote!(#diesel::dsl::Eq<#column, #ty>));
+            changesets.push(quote!(#diesel::ExpressionMethods::eq(#column, self.#ident)));
+        }
+    }
+
+    if changesets.is_empty() && has_primary_key {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "AsChangeset can't be derived for a struct of primary key columns, which are never \
+             updated",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #diesel::query_builder::AsChangeset for #name #ty_generics #where_clause {
+            type Target = #table_name::table;
+            type Changeset = <(#(#changeset_tys,)*) as #diesel::query_builder::AsChangeset>::Changeset;
+
+            fn as_changeset(self) -> Self::Changeset {
+                #diesel::query_builder::AsChangeset::as_changeset((#(#changesets,)*))
+            }
+        }
+    })
+}
+
+/// Parses `#[diesel(...)]` attributes, skipping the `other` keys `handle` doesn't recognise, as
+/// they belong to diesel's other derives, and rejecting the rest.
+fn parse_diesel_attrs(
+    attrs: &[Attribute],
+    other: &[&str],
+    mut handle: impl FnMut(&ParseNestedMeta) -> Result<bool>,
+) -> Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("diesel")) {
+        attr.parse_nested_meta(|meta| {
+            if handle(&meta)? {
+                return Ok(());
+            }
+            if !other.iter().any(|key| meta.path.is_ident(key)) {
+                let key = meta.path.to_token_stream();
+                return Err(meta.error(format_args!(
+                    "`{}` is not supported by `maybe::AsChangeset`",
+                    key
+                )));
+            }
+            if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in meta.input);
+                content.parse::<TokenStream>()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// Diesel's default table name: the struct name in snake case, with an `s` appended.
+fn default_table_name(name: &Ident) -> String {
+    let mut table_name = String::new();
+    for (index, c) in name.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if index > 0 {
+                table_name.push('_');
+            }
+            table_name.extend(c.to_lowercase());
+        } else {
+            table_name.push(c);
+        }
+    }
+    table_name.push('s');
+    table_name
+}
@@ -0,0 +1,107 @@
+use crate::{
+    options::{named_fields, parse_field_options, parse_item_options, serde_attrs},
+    ty::option_inner,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Result};
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let options = parse_item_options(&input.attrs, "diff", |_| Ok(false))?;
+    let fields = named_fields(&input, "Diff")?;
+
+    let (serde_container, serde_field) = serde_attrs(options.serde);
+    let mut field_decls = Vec::new();
+    let mut field_diffs = Vec::new();
+    let mut field_idents = Vec::new();
+    for field in fields {
+        let mut nested = false;
+        let field_options = parse_field_options(&field.attrs, "diff", |meta| {
+            if meta.path.is_ident("nested") {
+                nested = true;
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        })?;
+        if field_options.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        let vis = &field.vis;
+        let attrs = &field_options.attrs;
+        let ty = &field.ty;
+        // Marks nested diffs for the generators walking a patch, which set other values whole.
+        let nested_attr = if nested && options.serde {
+            quote!(#[serde(with = "::maybe::serde::nested")])
+        } else {
+            quote!()
+        };
+        let (diff_ty, diff) = if nested {
+            (
+                quote!(<#ty as ::maybe::Diff>::Output),
+                quote!(::maybe::Diff::diff(&self.#ident, &new.#ident)),
+            )
+        } else if let Some(inner) = option_inner(ty) {
+            (
+                quote!(#inner),
+                quote!(::maybe::Maybe::diff_option(&self.#ident, &new.#ident)),
+            )
+        } else {
+            (
+                quote!(#ty),
+                quote!(::maybe::Maybe::diff(&self.#ident, &new.#ident)),
+            )
+        };
+        field_decls.push(quote! {
+            #serde_field
+            #nested_attr
+            #(#[#attrs])*
+            #vis #ident: ::maybe::Maybe<#diff_ty>
+        });
+        field_diffs.push(quote!(#ident: #diff));
+        field_idents.push(ident);
+    }
+
+    let target = &input.ident;
+    let vis = &input.vis;
+    let name = options
+        .name
+        .unwrap_or_else(|| format_ident!("{}Diff", target));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &input.generics;
+    let derives = &options.derives;
+    let attrs = &options.attrs;
+
+    Ok(quote! {
+        #[derive(#(#derives),*)]
+        #serde_container
+        #(#[#attrs])*
+        #vis struct #name #generics #where_clause {
+            #(#field_decls,)*
+        }
+
+        impl #impl_generics ::core::default::Default for #name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#field_idents: ::maybe::Maybe::Void,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::maybe::Diff for #target #ty_generics #where_clause {
+            type Output = #name #ty_generics;
+
+            fn diff(&self, new: &Self) -> ::maybe::Maybe<Self::Output> {
+                let diff = #name {
+                    #(#field_diffs,)*
+                };
+                if true #(&& diff.#field_idents.is_void())* {
+                    ::maybe::Maybe::Void
+                } else {
+                    ::maybe::Maybe::Some(diff)
+                }
+            }
+        }
+    })
+}
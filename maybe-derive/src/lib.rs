@@ -0,0 +1,74 @@
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod changeset;
+mod diff;
+mod options;
+mod patch;
+mod serde_fields;
+mod ty;
+
+/// Generates a `{Name}Patch` struct of `Maybe` fields implementing `maybe::Patch` and
+/// `maybe::Compose`.
+///
+/// Container attributes: `#[patch(name = "...")]` renames the struct, `#[patch(derive(...))]`
+/// and `#[patch(attr(...))]` add derives and attributes to it, `#[patch(serde)]` derives
+/// `Serialize` and `Deserialize` skipping `Void` fields (with the `serde` feature of `maybe`),
+/// and `#[patch(invert)]` and `#[patch(merge)]` also implement `maybe::Invert` and
+/// `maybe::merge::Merge3`. Fields can be left out with `#[patch(skip)]` or given attributes with
+/// `#[patch(attr(...))]`.
+#[proc_macro_derive(Patch, attributes(patch))]
+pub fn derive_patch(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    patch::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `maybe::Diff`, generating a `{Name}Diff` struct of `Maybe` fields.
+///
+/// Unchanged fields are `Void`, `Option` fields changed to `None` are `None` and other changed
+/// fields hold their new value. Fields marked `#[diff(nested)]` hold the nested type's diff
+/// instead, serialized with `maybe::serde::nested` under `serde`. Accepts the same `name`, `serde`, `derive`, `attr` and `skip` options as `Patch`,
+/// under `#[diff(...)]`.
+#[proc_macro_derive(Diff, attributes(diff))]
+pub fn derive_diff(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    diff::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements diesel's `AsChangeset`, skipping `Maybe` and `Omittable` fields which are `Void`
+/// and setting `Maybe` fields which are `None` to `NULL`.
+///
+/// Reads diesel's `#[diesel(table_name = ...)]` and `#[diesel(primary_key(...))]` container
+/// attributes and `#[diesel(column_name = ...)]` and `#[diesel(skip_update)]` field attributes.
+/// As with diesel's derive, primary key columns (`id` by default) are never updated. Keys which
+/// only matter to diesel's other derives are ignored, and any others, such as
+/// `treat_none_as_null`, are rejected.
+#[proc_macro_derive(AsChangeset, attributes(diesel))]
+pub fn derive_as_changeset(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    changeset::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Adds `#[serde(default, skip_serializing_if = "Maybe::is_void")]` to every `Maybe` field
+/// (and the `Omittable` equivalent to `Omittable` fields).
+///
+/// Must be placed above `#[derive(Serialize, Deserialize)]`. The optional
+/// `void = "error" | "null" | "skip"` argument picks how `Void` elements of `Vec<Maybe<T>>`,
+/// `VecDeque<Maybe<T>>` and `[Maybe<T>; N]` fields are serialized, defaulting to an error. With
+/// `"null"` or `"skip"`, `Maybe` nested in any other shape (such as `Option<Vec<Maybe<T>>>` or
+/// map values) is a compile error unless the field has its own `serialize_with`.
+///
+/// `Maybe` fields marked `#[maybe(deny_null)]` or `#[maybe(deny_void)]` reject explicit nulls or
+/// missing values with an error naming the field.
+#[proc_macro_attribute]
+pub fn serde_fields(args: TokenStream, item: TokenStream) -> TokenStream {
+    serde_fields::expand(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,125 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    meta::ParseNestedMeta, parenthesized, punctuated::Punctuated, token::Comma, Attribute, Data,
+    DeriveInput, Field, Fields, Ident, LitStr, Path, Result,
+};
+
+/// Options shared by derives which generate a struct of `Maybe` fields.
+#[derive(Default)]
+pub struct ItemOptions {
+    pub name: Option<Ident>,
+    pub serde: bool,
+    pub derives: Vec<Path>,
+    pub attrs: Vec<TokenStream>,
+}
+
+#[derive(Default)]
+pub struct FieldOptions {
+    pub skip: bool,
+    pub attrs: Vec<TokenStream>,
+}
+
+/// Parses `#[<attr_name>(name = "...", serde, derive(...), attr(...))]`, passing any other keys to
+/// `extra`, which returns whether it handled them.
+pub fn parse_item_options(
+    attrs: &[Attribute],
+    attr_name: &str,
+    mut extra: impl FnMut(&ParseNestedMeta) -> Result<bool>,
+) -> Result<ItemOptions> {
+    let mut options = ItemOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(attr_name)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let name: LitStr = meta.value()?.parse()?;
+                options.name = Some(name.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("serde") {
+                options.serde = true;
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                meta.parse_nested_meta(|meta| {
+                    options.derives.push(meta.path);
+                    Ok(())
+                })
+            } else if meta.path.is_ident("attr") {
+                let content;
+                parenthesized!(content in meta.input);
+                options.attrs.push(content.parse()?);
+                Ok(())
+            } else if extra(&meta)? {
+                Ok(())
+            } else {
+                Err(meta.error(format!("unsupported {} attribute", attr_name)))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+/// Parses `#[<attr_name>(skip, attr(...))]` on a field, passing any other keys to `extra`.
+pub fn parse_field_options(
+    attrs: &[Attribute],
+    attr_name: &str,
+    mut extra: impl FnMut(&ParseNestedMeta) -> Result<bool>,
+) -> Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(attr_name)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                options.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("attr") {
+                let content;
+                parenthesized!(content in meta.input);
+                options.attrs.push(content.parse()?);
+                Ok(())
+            } else if extra(&meta)? {
+                Ok(())
+            } else {
+                Err(meta.error(format!("unsupported {} field attribute", attr_name)))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+pub fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> Result<&'a Punctuated<Field, Comma>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(&fields.named),
+            _ => Err(syn::Error::new_spanned(
+                &input.ident,
+                format!(
+                    "{} can only be derived for structs with named fields",
+                    derive
+                ),
+            )),
+        },
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            format!("{} can only be derived for structs", derive),
+        )),
+    }
+}
+
+/// The serde attributes for generated structs and their `Maybe` fields, which are empty unless
+/// `enabled`.
+pub fn serde_attrs(enabled: bool) -> (TokenStream, TokenStream) {
+    if enabled {
+        (
+            quote! {
+                #[derive(::maybe::__private::serde::Serialize, ::maybe::__private::serde::Deserialize)]
+                #[serde(crate = "::maybe::__private::serde")]
+            },
+            quote! {
+                #[serde(default, skip_serializing_if = "::maybe::Maybe::is_void")]
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{
+    options::{named_fields, parse_field_options, parse_item_options, serde_attrs},
+    ty::option_inner,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Ident, Result, Visibility};
+
+struct PatchField {
+    ident: Ident,
+    vis: Visibility,
+    ty: syn::Type,
+    optional: bool,
+    attrs: Vec<TokenStream>,
+}
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let mut merge = false;
+    let mut invert = false;
+    let options = parse_item_options(&input.attrs, "patch", |meta| {
+        if meta.path.is_ident("merge") {
+            merge = true;
+        } else if meta.path.is_ident("invert") {
+            invert = true;
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    })?;
+    let fields = named_fields(&input, "Patch")?;
+
+    let mut patch_fields = Vec::new();
+    for field in fields {
+        let field_options = parse_field_options(&field.attrs, "patch", |_| Ok(false))?;
+        if field_options.skip {
+            continue;
+        }
+        let (ty, optional) = match option_inner(&field.ty) {
+            Some(inner) => (inner.clone(), true),
+            None => (field.ty.clone(), false),
+        };
+        patch_fields.push(PatchField {
+            ident: field.ident.clone().expect("named field"),
+            vis: field.vis.clone(),
+            ty,
+            optional,
+            attrs: field_options.attrs,
+        });
+    }
+
+    let target = &input.ident;
+    let vis = &input.vis;
+    let name = options
+        .name
+        .unwrap_or_else(|| format_ident!("{}Patch", target));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &input.generics;
+
+    let derives = &options.derives;
+    let attrs = &options.attrs;
+    let (serde_container, serde_field) = serde_attrs(options.serde);
+
+    let field_decls = patch_fields.iter().map(|field| {
+        let PatchField {
+            ident,
+            vis,
+            ty,
+            attrs,
+            ..
+        } = field;
+        quote! {
+            #serde_field
+            #(#[#attrs])*
+            #vis #ident: ::maybe::Maybe<#ty>
+        }
+    });
+    let field_idents = patch_fields.iter().map(|field| &field.ident);
+    let required_checks = patch_fields
+        .iter()
+        .filter(|field| !field.optional)
+        .map(|field| {
+            let ident = &field.ident;
+            let field_name = ident.to_string();
+            quote! {
+                if self.#ident.is_none() {
+                    return ::core::result::Result::Err(::maybe::PatchError::null_field(#field_name));
+                }
+            }
+        });
+    let assignments = patch_fields.iter().map(|field| {
+        let ident = &field.ident;
+        if field.optional {
+            quote! {
+                match self.#ident {
+                    ::maybe::Maybe::Void => {}
+                    ::maybe::Maybe::None => target.#ident = ::core::option::Option::None,
+                    ::maybe::Maybe::Some(value) => target.#ident = ::core::option::Option::Some(value),
+                }
+            }
+        } else {
+            quote! {
+                if let ::maybe::Maybe::Some(value) = self.#ident {
+                    target.#ident = value;
+                }
+            }
+        }
+    });
+
+    let composed_fields = patch_fields.iter().map(|field| {
+        let ident = &field.ident;
+        quote!(#ident: ::maybe::Maybe::compose(self.#ident, later.#ident))
+    });
+    let invert_impl = invert.then(|| {
+        let inverted_fields = patch_fields.iter().map(|field| {
+            let ident = &field.ident;
+            if field.optional {
+                quote!(#ident: self.#ident.invert(original.#ident.as_ref()))
+            } else {
+                quote!(#ident: self.#ident.invert(::core::option::Option::Some(&original.#ident)))
+            }
+        });
+        quote! {
+            impl #impl_generics ::maybe::Invert for #name #ty_generics #where_clause {
+                fn invert(&self, original: &Self::Target) -> Self {
+                    Self {
+                        #(#inverted_fields,)*
+                    }
+                }
+            }
+        }
+    });
+    let merge_impl = merge.then(|| {
+        let merged_fields = patch_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let field_name = ident.to_string();
+            let base = if field.optional {
+                quote!(base.#ident.as_ref())
+            } else {
+                quote!(::core::option::Option::Some(&base.#ident))
+            };
+            quote! {
+                #ident: ::maybe::merge::merge_field(
+                    #field_name,
+                    #base,
+                    ours.#ident,
+                    theirs.#ident,
+                    strategy,
+                    &mut conflicts,
+                )
+            }
+        });
+        quote! {
+            impl #impl_generics ::maybe::merge::Merge3 for #name #ty_generics #where_clause {
+                fn merge3<S>(
+                    base: &Self::Target,
+                    ours: Self,
+                    theirs: Self,
+                    strategy: &mut S,
+                ) -> ::core::result::Result<Self, ::maybe::merge::MergeError>
+                where
+                    S: ::maybe::merge::Strategy,
+                {
+                    let mut conflicts = ::std::vec::Vec::new();
+                    let merged = Self {
+                        #(#merged_fields,)*
+                    };
+                    if conflicts.is_empty() {
+                        ::core::result::Result::Ok(merged)
+                    } else {
+                        ::core::result::Result::Err(::maybe::merge::MergeError::new(conflicts))
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[derive(#(#derives),*)]
+        #serde_container
+        #(#[#attrs])*
+        #vis struct #name #generics #where_clause {
+            #(#field_decls,)*
+        }
+
+        impl #impl_generics ::core::default::Default for #name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#field_idents: ::maybe::Maybe::Void,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::maybe::Patch for #name #ty_generics #where_clause {
+            type Target = #target #ty_generics;
+
+            fn apply(self, target: &mut Self::Target) -> ::core::result::Result<(), ::maybe::PatchError> {
+                #(#required_checks)*
+                #(#assignments)*
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        impl #impl_generics ::maybe::Compose for #name #ty_generics #where_clause {
+            fn compose(self, later: Self) -> Self {
+                Self {
+                    #(#composed_fields,)*
+                }
+            }
+        }
+
+        #invert_impl
+        #merge_impl
+    })
+}
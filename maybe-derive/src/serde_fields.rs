@@ -0,0 +1,206 @@
+use crate::ty::{contains_maybe, maybe_inner, omittable_inner, seq_inner};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{parse::Parser, parse_quote, Attribute, Field, Fields, Item, LitStr, Meta, Result};
+
+#[derive(Clone, Copy, Default)]
+enum VoidPolicy {
+    #[default]
+    Error,
+    Null,
+    Skip,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Deny {
+    Null,
+    Void,
+}
+
+pub fn expand(args: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let policy = parse_policy(args)?;
+    let mut item: Item = syn::parse2(item)?;
+    let mut helpers = Vec::new();
+    match &mut item {
+        Item::Struct(item) => {
+            let prefix = item.ident.to_string();
+            rewrite_fields(&mut item.fields, policy, &prefix, &mut helpers)?;
+        }
+        Item::Enum(item) => {
+            for variant in &mut item.variants {
+                let prefix = format!("{}_{}", item.ident, variant.ident);
+                rewrite_fields(&mut variant.fields, policy, &prefix, &mut helpers)?;
+            }
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                item,
+                "serde_fields can only be used on structs and enums",
+            ))
+        }
+    }
+    Ok(quote! {
+        #item
+        #(#helpers)*
+    })
+}
+
+fn parse_policy(args: TokenStream) -> Result<VoidPolicy> {
+    let mut policy = VoidPolicy::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("void") {
+            let value: LitStr = meta.value()?.parse()?;
+            policy = match value.value().as_str() {
+                "error" => VoidPolicy::Error,
+                "null" => VoidPolicy::Null,
+                "skip" => VoidPolicy::Skip,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "expected one of \"error\", \"null\" or \"skip\"",
+                    ))
+                }
+            };
+            Ok(())
+        } else {
+            Err(meta.error("unsupported serde_fields argument"))
+        }
+    });
+    parser.parse2(args)?;
+    Ok(policy)
+}
+
+fn rewrite_fields(
+    fields: &mut Fields,
+    policy: VoidPolicy,
+    prefix: &str,
+    helpers: &mut Vec<TokenStream>,
+) -> Result<()> {
+    let Fields::Named(fields) = fields else {
+        return Ok(());
+    };
+    for field in &mut fields.named {
+        let deny = take_deny(field)?;
+        let (is_void, nested) = if let Some(inner) = maybe_inner(&field.ty) {
+            (Some("::maybe::Maybe::is_void"), contains_maybe(inner))
+        } else if let Some(inner) = omittable_inner(&field.ty) {
+            (Some("::maybe::Omittable::is_void"), contains_maybe(inner))
+        } else if let Some(inner) = seq_inner(&field.ty).and_then(maybe_inner) {
+            (None, contains_maybe(inner))
+        } else {
+            (None, contains_maybe(&field.ty))
+        };
+        let custom = has_serde_key(field, "serialize_with") || has_serde_key(field, "with");
+        if nested && !custom && !matches!(policy, VoidPolicy::Error) {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "the `void` policy only applies to `Vec<Maybe<T>>`, `VecDeque<Maybe<T>>` and \
+                 `[Maybe<T>; N]` fields; use `serialize_with` for other shapes",
+            ));
+        }
+        if let Some(is_void) = is_void {
+            if deny != Some(Deny::Void) && !has_serde_key(field, "default") {
+                field.attrs.push(parse_quote!(#[serde(default)]));
+            }
+            if !has_serde_key(field, "skip_serializing_if") {
+                field
+                    .attrs
+                    .push(parse_quote!(#[serde(skip_serializing_if = #is_void)]));
+            }
+        } else if seq_inner(&field.ty).is_some_and(|inner| maybe_inner(inner).is_some()) && !custom
+        {
+            match policy {
+                VoidPolicy::Error => {}
+                VoidPolicy::Null => field.attrs.push(parse_quote!(
+                    #[serde(serialize_with = "::maybe::serde::seq_void_as_null")]
+                )),
+                VoidPolicy::Skip => field.attrs.push(parse_quote!(
+                    #[serde(serialize_with = "::maybe::serde::seq_skip_void")]
+                )),
+            }
+        }
+
+        let ident = field.ident.as_ref().expect("named field");
+        match deny {
+            Some(Deny::Null) => {
+                let name = ident.to_string();
+                let helper = format_ident!("__maybe_deny_null_{}_{}", prefix, ident);
+                let helper_path = helper.to_string();
+                helpers.push(quote! {
+                    #[doc(hidden)]
+                    #[allow(non_snake_case)]
+                    fn #helper<'de, D, T>(deserializer: D) -> ::core::result::Result<::maybe::Maybe<T>, D::Error>
+                    where
+                        D: ::maybe::__private::serde::Deserializer<'de>,
+                        T: ::maybe::__private::serde::Deserialize<'de>,
+                    {
+                        ::maybe::serde::deny_null::deserialize_field(deserializer, #name)
+                    }
+                });
+                field.attrs.push(parse_quote!(#[serde(
+                    serialize_with = "::maybe::serde::deny_null::serialize",
+                    deserialize_with = #helper_path
+                )]));
+            }
+            Some(Deny::Void) => {
+                field
+                    .attrs
+                    .push(parse_quote!(#[serde(with = "::maybe::serde::deny_void")]));
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Removes `#[maybe(deny_null)]` and `#[maybe(deny_void)]` from the field, returning which one
+/// was present.
+fn take_deny(field: &mut Field) -> Result<Option<Deny>> {
+    let mut deny = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("maybe"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("deny_null") {
+                deny = Some(Deny::Null);
+                Ok(())
+            } else if meta.path.is_ident("deny_void") {
+                deny = Some(Deny::Void);
+                Ok(())
+            } else {
+                Err(meta.error("expected `deny_null` or `deny_void`"))
+            }
+        })?;
+    }
+    field.attrs.retain(|attr| !attr.path().is_ident("maybe"));
+    Ok(deny)
+}
+
+fn has_serde_key(field: &Field, key: &str) -> bool {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .any(|attr| serde_keys(attr).iter().any(|name| name == key))
+}
+
+fn serde_keys(attr: &Attribute) -> Vec<String> {
+    let Meta::List(list) = &attr.meta else {
+        return Vec::new();
+    };
+    let mut keys = Vec::new();
+    let mut at_start = true;
+    for token in list.tokens.clone() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => at_start = true,
+            TokenTree::Ident(ident) if at_start => {
+                keys.push(ident.to_string());
+                at_start = false;
+            }
+            _ => at_start = false,
+        }
+    }
+    keys
+}
@@ -0,0 +1,69 @@
+use syn::{GenericArgument, PathArguments, Type};
+
+/// Returns `T` if `ty` looks like `Option<T>` (or a path ending in `Option<T>`).
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Option")
+}
+
+/// Returns `T` if `ty` looks like `Maybe<T>`.
+pub fn maybe_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Maybe")
+}
+
+/// Returns `T` if `ty` looks like `Omittable<T>`.
+pub fn omittable_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Omittable")
+}
+
+/// Returns `T` if `ty` looks like `Vec<T>`, `VecDeque<T>` or `[T; N]`.
+pub fn seq_inner(ty: &Type) -> Option<&Type> {
+    if let Type::Array(array) = ty {
+        return Some(&array.elem);
+    }
+    generic_inner(ty, "Vec").or_else(|| generic_inner(ty, "VecDeque"))
+}
+
+/// Whether `Maybe` appears anywhere in `ty`.
+pub fn contains_maybe(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.iter().any(|segment| {
+            segment.ident == "Maybe"
+                || match &segment.arguments {
+                    PathArguments::AngleBracketed(arguments) => {
+                        arguments.args.iter().any(|argument| match argument {
+                            GenericArgument::Type(ty) => contains_maybe(ty),
+                            _ => false,
+                        })
+                    }
+                    _ => false,
+                }
+        }),
+        Type::Array(array) => contains_maybe(&array.elem),
+        Type::Slice(slice) => contains_maybe(&slice.elem),
+        Type::Reference(reference) => contains_maybe(&reference.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().any(contains_maybe),
+        Type::Group(group) => contains_maybe(&group.elem),
+        Type::Paren(paren) => contains_maybe(&paren.elem),
+        _ => false,
+    }
+}
+
+fn generic_inner<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let segment = path.path.segments.last()?;
+    if segment.ident != name {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first() {
+        Some(GenericArgument::Type(inner)) if arguments.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{Maybe, PatchError};
+use sea_orm::{ActiveValue, Value};
+
+/// `Void` becomes `NotSet`, while `None` and `Some` are `Set`.
+impl<T> From<Maybe<T>> for ActiveValue<Option<T>>
+where
+    Option<T>: Into<Value>,
+{
+    fn from(value: Maybe<T>) -> Self {
+        match value {
+            Maybe::Void => ActiveValue::NotSet,
+            value => ActiveValue::Set(value.into()),
+        }
+    }
+}
+
+impl<T> Maybe<T> {
+    /// Converts into the `ActiveValue` of a nullable column.
+    pub fn into_active_value(self) -> ActiveValue<Option<T>>
+    where
+        Option<T>: Into<Value>,
+    {
+        self.into()
+    }
+
+    /// Converts into the `ActiveValue` of a non-nullable column, failing with the name of the
+    /// column if `self` is `None`.
+    pub fn try_into_active_value(self, column: &'static str) -> Result<ActiveValue<T>, PatchError>
+    where
+        T: Into<Value>,
+    {
+        match self {
+            Self::Void => Ok(ActiveValue::NotSet),
+            Self::None => Err(PatchError::null_field(column)),
+            Self::Some(value) => Ok(ActiveValue::Set(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn it_converts_into_nullable_active_values() {
+        assert_eq!(Maybe::<i32>::Void.into_active_value(), ActiveValue::NotSet);
+        assert_eq!(
+            Maybe::<i32>::None.into_active_value(),
+            ActiveValue::Set(None)
+        );
+        assert_eq!(ActiveValue::from(Maybe::Some(1)), ActiveValue::Set(Some(1)));
+    }
+
+    #[test]
+    pub fn it_converts_into_non_nullable_active_values() {
+        assert_eq!(
+            Maybe::<String>::Void.try_into_active_value("name"),
+            Ok(ActiveValue::NotSet)
+        );
+        assert_eq!(
+            Maybe::Some("Ferris".to_string()).try_into_active_value("name"),
+            Ok(ActiveValue::Set("Ferris".to_string()))
+        );
+        let error = Maybe::<String>::None
+            .try_into_active_value("name")
+            .unwrap_err();
+        assert_eq!(error.field(), "name");
+    }
+}
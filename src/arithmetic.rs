@@ -0,0 +1,153 @@
+//! Arithmetic on `Maybe` values, propagating `None` like `NULL` in SQL.
+//!
+//! `None` is unknown, so any operation involving it is `None`, even when the other operand is
+//! `Void`. `Void` is an absent operand, which leaves the other one alone: it is the identity of
+//! `+` and `*` on either side, and of `-` and `/` on the right. `Void - x` and `Void / x` are
+//! `Void`, as there is nothing to subtract from or divide. Negating `Void` or `None` leaves it
+//! unchanged.
+
+use crate::Maybe;
+use std::{
+    iter::{Product, Sum},
+    mem,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+fn combine<T>(
+    lhs: Maybe<T>,
+    rhs: Maybe<T>,
+    left_identity: bool,
+    op: impl FnOnce(T, T) -> T,
+) -> Maybe<T> {
+    match (lhs, rhs) {
+        (Maybe::None, _) | (_, Maybe::None) => Maybe::None,
+        (Maybe::Some(lhs), Maybe::Some(rhs)) => Maybe::Some(op(lhs, rhs)),
+        (lhs, Maybe::Void) => lhs,
+        (Maybe::Void, rhs) if left_identity => rhs,
+        (Maybe::Void, _) => Maybe::Void,
+    }
+}
+
+macro_rules! binary_op {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $left_identity:expr) => {
+        impl<T: $Op<Output = T>> $Op for Maybe<T> {
+            type Output = Self;
+
+            fn $op(self, rhs: Self) -> Self {
+                combine(self, rhs, $left_identity, $Op::$op)
+            }
+        }
+
+        impl<T: $Op<Output = T>> $OpAssign for Maybe<T> {
+            fn $op_assign(&mut self, rhs: Self) {
+                *self = $Op::$op(mem::take(self), rhs);
+            }
+        }
+    };
+}
+
+binary_op!(Add, add, AddAssign, add_assign, true);
+binary_op!(Sub, sub, SubAssign, sub_assign, false);
+binary_op!(Mul, mul, MulAssign, mul_assign, true);
+binary_op!(Div, div, DivAssign, div_assign, false);
+
+impl<T: Neg<Output = T>> Neg for Maybe<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(Neg::neg)
+    }
+}
+
+/// Adds the `Some` values, skipping `Void` ones. Stops at the first `None`, which is the result,
+/// and returns `Void` if every value is `Void`, including when there are none.
+impl<T: Add<Output = T>> Sum for Maybe<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        fold(iter, |lhs, rhs| lhs + rhs)
+    }
+}
+
+/// Multiplies the `Some` values, with the same handling of `Void` and `None` as [`Sum`].
+impl<T: Mul<Output = T>> Product for Maybe<T> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        fold(iter, |lhs, rhs| lhs * rhs)
+    }
+}
+
+fn fold<T>(
+    iter: impl Iterator<Item = Maybe<T>>,
+    op: impl Fn(Maybe<T>, Maybe<T>) -> Maybe<T>,
+) -> Maybe<T> {
+    let mut result = Maybe::Void;
+    for value in iter {
+        if value.is_none() {
+            return Maybe::None;
+        }
+        result = op(result, value);
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn it_propagates_none() {
+        for value in [Maybe::Some(2), Maybe::None, Maybe::Void] {
+            assert_eq!(value + Maybe::None, Maybe::None);
+            assert_eq!(Maybe::None - value, Maybe::None);
+            assert_eq!(value * Maybe::None, Maybe::None);
+            assert_eq!(Maybe::None / value, Maybe::None);
+        }
+        assert_eq!(-Maybe::<i32>::None, Maybe::None);
+    }
+
+    #[test]
+    pub fn it_applies_operators_to_some() {
+        assert_eq!(Maybe::Some(6) + Maybe::Some(2), Maybe::Some(8));
+        assert_eq!(Maybe::Some(6) - Maybe::Some(2), Maybe::Some(4));
+        assert_eq!(Maybe::Some(6) * Maybe::Some(2), Maybe::Some(12));
+        assert_eq!(Maybe::Some(6) / Maybe::Some(2), Maybe::Some(3));
+        assert_eq!(-Maybe::Some(6), Maybe::Some(-6));
+    }
+
+    #[test]
+    pub fn it_treats_void_as_absent() {
+        assert_eq!(Maybe::Void + Maybe::Some(2), Maybe::Some(2));
+        assert_eq!(Maybe::Some(2) + Maybe::Void, Maybe::Some(2));
+        assert_eq!(Maybe::Void * Maybe::Some(2), Maybe::Some(2));
+        assert_eq!(Maybe::Some(6) - Maybe::Void, Maybe::Some(6));
+        assert_eq!(Maybe::Some(6) / Maybe::Void, Maybe::Some(6));
+        assert_eq!(Maybe::Void - Maybe::Some(2), Maybe::Void);
+        assert_eq!(Maybe::Void / Maybe::Some(2), Maybe::Void);
+        assert_eq!(Maybe::<i32>::Void + Maybe::Void, Maybe::Void);
+        assert_eq!(-Maybe::<i32>::Void, Maybe::Void);
+    }
+
+    #[test]
+    pub fn it_assigns_results() {
+        let mut total = Maybe::Void;
+        total += Maybe::Some(3);
+        total *= Maybe::Some(4);
+        total -= Maybe::Void;
+        total /= Maybe::Some(2);
+        assert_eq!(total, Maybe::Some(6));
+        total -= Maybe::None;
+        assert_eq!(total, Maybe::None);
+    }
+
+    #[test]
+    pub fn it_sums_and_multiplies_iterators() {
+        let values = [Maybe::Some(2), Maybe::Void, Maybe::Some(3)];
+        assert_eq!(values.into_iter().sum::<Maybe<i32>>(), Maybe::Some(5));
+        assert_eq!(values.into_iter().product::<Maybe<i32>>(), Maybe::Some(6));
+
+        let values = [Maybe::Some(2), Maybe::None, Maybe::Some(3)];
+        assert_eq!(values.into_iter().sum::<Maybe<i32>>(), Maybe::None);
+        assert_eq!(values.into_iter().product::<Maybe<i32>>(), Maybe::None);
+
+        assert_eq!([Maybe::Void].into_iter().sum::<Maybe<i32>>(), Maybe::Void);
+        assert_eq!(std::iter::empty().product::<Maybe<i32>>(), Maybe::Void);
+    }
+}
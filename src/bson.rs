@@ -0,0 +1,240 @@
+//! BSON values and MongoDB update documents.
+//!
+//! In a document, a missing field is `Void`, `null` is `None` and any other value is `Some`.
+//! [`update_document`] turns a patch into the `$set` and `$unset` operators of an update.
+
+use crate::{serde::nested, Maybe};
+use ::bson::{de, ser, Bson, Document};
+use ::serde::{de::DeserializeOwned, Serialize};
+
+impl<T> Maybe<T> {
+    /// Reads the value of a document field, where `None` means the field is missing.
+    pub fn from_bson(value: Option<Bson>) -> de::Result<Self>
+    where
+        T: DeserializeOwned,
+    {
+        match value {
+            None => Ok(Self::Void),
+            Some(Bson::Null) => Ok(Self::None),
+            Some(value) => ::bson::from_bson(value).map(Self::Some),
+        }
+    }
+
+    /// Converts into the value of a document field, returning `None` if `self` is `Void` and
+    /// the field should be left out.
+    pub fn into_bson(self) -> ser::Result<Option<Bson>>
+    where
+        T: Serialize,
+    {
+        match self {
+            Self::Void => Ok(None),
+            Self::None => Ok(Some(Bson::Null)),
+            Self::Some(value) => ::bson::to_bson(&value).map(Some),
+        }
+    }
+}
+
+/// Builds an update document for a struct of `Maybe` fields, which must skip `Void` fields when
+/// serialized. `None` fields are `$unset` and other fields are `$set` whole, except for fields
+/// marked with [`crate::serde::nested`], whose own fields are updated individually using dotted
+/// paths. Operators without any fields are left out.
+pub fn update_document<T: Serialize>(patch: &T) -> ser::Result<Document> {
+    let mut set = Document::new();
+    let mut unset = Document::new();
+    let patch = nested::tagged(|| ::bson::to_document(patch))?;
+    collect(None, &patch, &mut set, &mut unset);
+
+    let mut update = Document::new();
+    if !set.is_empty() {
+        update.insert("$set", set);
+    }
+    if !unset.is_empty() {
+        update.insert("$unset", unset);
+    }
+    Ok(update)
+}
+
+fn collect(prefix: Option<&str>, patch: &Document, set: &mut Document, unset: &mut Document) {
+    for (name, value) in patch {
+        let path = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, name),
+            None => name.clone(),
+        };
+        match (value, tagged_value(value)) {
+            (_, Some(Bson::Document(nested))) => collect(Some(&path), nested, set, unset),
+            (Bson::Null, _) => {
+                unset.insert(path, "");
+            }
+            (_, Some(value)) | (value, None) => {
+                set.insert(path, untag(value));
+            }
+        }
+    }
+}
+
+/// Returns the value of a marked field, which is a nested patch if it is a document.
+fn tagged_value(value: &Bson) -> Option<&Bson> {
+    match value {
+        Bson::Document(document) => nested::tagged_value(document.len(), document.iter().next()),
+        _ => None,
+    }
+}
+
+/// Copies a value which is `$set` whole, unwrapping the values of the marked fields within it.
+fn untag(value: &Bson) -> Bson {
+    match value {
+        Bson::Document(document) => match tagged_value(value) {
+            Some(value) => untag(value),
+            None => Bson::Document(
+                document
+                    .iter()
+                    .map(|(name, value)| (name.clone(), untag(value)))
+                    .collect(),
+            ),
+        },
+        Bson::Array(items) => Bson::Array(items.iter().map(untag).collect()),
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::bson::doc;
+    use ::serde::Deserialize;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct ProfilePatch {
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        bio: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        tags: Maybe<Vec<String>>,
+        #[serde(
+            default,
+            skip_serializing_if = "Maybe::is_void",
+            with = "crate::serde::nested"
+        )]
+        settings: Maybe<SettingsPatch>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        avatar: Maybe<Avatar>,
+    }
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct SettingsPatch {
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        theme: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        locale: Maybe<String>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Avatar {
+        url: String,
+        alt: Option<String>,
+    }
+
+    #[test]
+    pub fn it_converts_to_and_from_bson() {
+        let document = doc! { "theme": "dark", "bio": null };
+        assert_eq!(
+            Maybe::<String>::from_bson(document.get("theme").cloned()).unwrap(),
+            Maybe::Some("dark".to_string())
+        );
+        assert_eq!(
+            Maybe::<String>::from_bson(document.get("bio").cloned()).unwrap(),
+            Maybe::None
+        );
+        assert_eq!(
+            Maybe::<String>::from_bson(document.get("tags").cloned()).unwrap(),
+            Maybe::Void
+        );
+        assert!(Maybe::<i32>::from_bson(document.get("theme").cloned()).is_err());
+
+        assert_eq!(Maybe::Some(1).into_bson().unwrap(), Some(Bson::Int32(1)));
+        assert_eq!(Maybe::<i32>::None.into_bson().unwrap(), Some(Bson::Null));
+        assert_eq!(Maybe::<i32>::Void.into_bson().unwrap(), None);
+    }
+
+    #[test]
+    pub fn it_builds_update_documents() {
+        let patch = ProfilePatch {
+            bio: Maybe::None,
+            tags: Maybe::Some(vec!["rust".to_string()]),
+            settings: Maybe::Some(SettingsPatch {
+                theme: Maybe::Some("dark".to_string()),
+                locale: Maybe::None,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            update_document(&patch).unwrap(),
+            doc! {
+                "$set": { "tags": ["rust"], "settings.theme": "dark" },
+                "$unset": { "bio": "", "settings.locale": "" },
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_sets_values_which_are_not_nested_patches_whole() {
+        let patch = ProfilePatch {
+            avatar: Maybe::Some(Avatar {
+                url: "avatar.png".to_string(),
+                alt: None,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            update_document(&patch).unwrap(),
+            doc! { "$set": { "avatar": { "url": "avatar.png", "alt": null } } }
+        );
+    }
+
+    #[test]
+    pub fn it_sets_marked_values_which_are_not_documents_whole() {
+        #[derive(Serialize)]
+        struct NicknamePatch {
+            #[serde(with = "crate::serde::nested")]
+            nickname: Maybe<String>,
+        }
+
+        let patch = NicknamePatch {
+            nickname: Maybe::Some("Ferris".to_string()),
+        };
+        assert_eq!(
+            update_document(&patch).unwrap(),
+            doc! { "$set": { "nickname": "Ferris" } }
+        );
+    }
+
+    #[test]
+    pub fn it_leaves_out_empty_operators() {
+        let patch = ProfilePatch {
+            bio: Maybe::None,
+            settings: Maybe::Some(SettingsPatch::default()),
+            ..Default::default()
+        };
+        assert_eq!(
+            update_document(&patch).unwrap(),
+            doc! { "$unset": { "bio": "" } }
+        );
+        assert_eq!(update_document(&ProfilePatch::default()).unwrap(), doc! {});
+    }
+
+    #[test]
+    pub fn it_deserializes_patches_from_bson() {
+        let patch: ProfilePatch =
+            ::bson::from_document(doc! { "bio": null, "settings": { "theme": "dark" } }).unwrap();
+        assert_eq!(
+            patch,
+            ProfilePatch {
+                bio: Maybe::None,
+                settings: Maybe::Some(SettingsPatch {
+                    theme: Maybe::Some("dark".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        );
+    }
+}
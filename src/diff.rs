@@ -0,0 +1,139 @@
+use crate::Maybe;
+
+/// Computes the changes between two values as `Maybe` fields.
+///
+/// Usually implemented by `#[derive(Diff)]` (with the `derive` feature). The diff is `Void` if
+/// nothing changed, and its fields are `Void` where unchanged, `None` where changed to absent
+/// and `Some` with the new value otherwise.
+pub trait Diff {
+    type Output;
+
+    fn diff(&self, new: &Self) -> Maybe<Self::Output>;
+}
+
+impl<T> Maybe<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Returns `Void` if `old` and `new` are equal, and `Some(new)` otherwise.
+    pub fn diff(old: &T, new: &T) -> Self {
+        if old == new {
+            Self::Void
+        } else {
+            Self::Some(new.clone())
+        }
+    }
+
+    /// Returns `Void` if `old` and `new` are equal, and `new` as a `Maybe` otherwise.
+    pub fn diff_option(old: &Option<T>, new: &Option<T>) -> Self {
+        if old == new {
+            Self::Void
+        } else {
+            new.clone().into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn it_diffs_values() {
+        assert_eq!(Maybe::diff(&1, &1), Maybe::Void);
+        assert_eq!(Maybe::diff(&1, &2), Maybe::Some(2));
+        assert_eq!(Maybe::diff_option(&Some(1), &Some(1)), Maybe::Void);
+        assert_eq!(Maybe::diff_option(&Some(1), &None), Maybe::None);
+        assert_eq!(Maybe::diff_option(&None, &Some(2)), Maybe::Some(2));
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod derive_test {
+    use crate::{Diff, Maybe};
+
+    #[derive(Diff, Clone)]
+    #[diff(derive(Debug, PartialEq))]
+    #[cfg_attr(feature = "serde", diff(serde))]
+    struct User {
+        name: String,
+        email: Option<String>,
+        #[diff(nested)]
+        address: Address,
+        #[diff(skip)]
+        #[allow(dead_code)]
+        visits: u32,
+    }
+
+    #[derive(Diff, Clone)]
+    #[diff(derive(Debug, PartialEq))]
+    #[cfg_attr(feature = "serde", diff(serde))]
+    struct Address {
+        city: String,
+        zip: Option<String>,
+    }
+
+    fn user() -> User {
+        User {
+            name: "Ferris".into(),
+            email: Some("ferris@example.com".into()),
+            address: Address {
+                city: "Crabtown".into(),
+                zip: Some("1234".into()),
+            },
+            visits: 1,
+        }
+    }
+
+    #[test]
+    pub fn it_is_void_when_nothing_changed() {
+        let mut new = user();
+        new.visits = 2;
+        assert_eq!(user().diff(&new), Maybe::Void);
+    }
+
+    #[test]
+    pub fn it_diffs_changed_fields() {
+        let mut new = user();
+        new.email = None;
+        new.address.city = "Rustville".into();
+        assert_eq!(
+            user().diff(&new),
+            Maybe::Some(UserDiff {
+                name: Maybe::Void,
+                email: Maybe::None,
+                address: Maybe::Some(AddressDiff {
+                    city: Maybe::Some("Rustville".into()),
+                    zip: Maybe::Void,
+                }),
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn it_serializes_diffs() {
+        let mut new = user();
+        new.name = "Corro".into();
+        new.address.zip = None;
+        let json = serde_json::to_string(&user().diff(&new).unwrap()).unwrap();
+        assert_eq!(json, r#"{"name":"Corro","address":{"zip":null}}"#);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    pub fn it_patches_nested_diffs_field_by_field() {
+        use crate::json_patch::{operations, Operation, Options};
+
+        let mut new = user();
+        new.address.city = "Rustville".into();
+        let operations = operations(&user().diff(&new).unwrap(), Options::default()).unwrap();
+        assert_eq!(
+            operations,
+            vec![Operation::Replace {
+                path: "/address/city".into(),
+                value: "Rustville".into(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Field masks: lists of dotted paths naming the fields a partial update touches.
+//!
+//! With the `json` feature, masks can be built from the serialized form of a patch, as for
+//! `maybe::merge_patch`, so they use serialized field names. Every field which isn't `Void` is
+//! in the mask, with fields marked with [`crate::serde::nested`] contributing the paths of their
+//! own fields instead, so an empty nested patch adds nothing. Paths are sorted, whatever the
+//! order of the fields.
+
+use crate::Maybe;
+#[cfg(feature = "json")]
+use crate::{json_patch::nested_patch, merge_patch, serde::nested};
+#[cfg(feature = "json")]
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "json")]
+use serde_json::{Map, Value};
+
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct FieldMask {
+    pub paths: Vec<String>,
+}
+
+impl FieldMask {
+    pub fn new<I, S>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            paths: paths.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns `Void` if `path` isn't in the mask, and `value` as a `Maybe` otherwise, so that
+    /// masked fields without a value become `None`.
+    pub fn masked<T>(&self, path: &str, value: Option<T>) -> Maybe<T> {
+        if self.contains(path) {
+            value.into()
+        } else {
+            Maybe::Void
+        }
+    }
+
+    /// Adds `path` to the mask unless `value` is `Void`, returning the value as an `Option`.
+    pub fn record<T>(&mut self, path: &str, value: Maybe<T>) -> Option<T> {
+        if value.is_defined() {
+            self.paths.push(path.to_string());
+        }
+        value.into()
+    }
+
+    /// Returns `true` if `path` or one of its parents is in the mask.
+    pub fn contains(&self, path: &str) -> bool {
+        self.paths.iter().any(|masked| {
+            path.strip_prefix(masked.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    }
+
+    /// Returns the paths of every field of `patch` which isn't `Void`.
+    #[cfg(feature = "json")]
+    pub fn from_patch<P>(patch: &P) -> serde_json::Result<Self>
+    where
+        P: Serialize,
+    {
+        let document = nested::tagged(|| merge_patch::to_document(patch))?;
+        Ok(Self::from_members(&document, true))
+    }
+
+    /// Returns the paths of every member of a merge patch document, where every object is a
+    /// nested patch.
+    #[cfg(feature = "json")]
+    pub fn from_document(document: &Value) -> Self {
+        Self::from_members(document, false)
+    }
+
+    #[cfg(feature = "json")]
+    fn from_members(document: &Value, tagged: bool) -> Self {
+        let mut paths = Vec::new();
+        if let Value::Object(members) = document {
+            push_paths(&mut paths, None, members, tagged);
+        }
+        paths.sort();
+        Self { paths }
+    }
+
+    /// Builds a merge patch document holding the masked members of `value`. Masked members
+    /// missing from `value` are set to `null`.
+    #[cfg(feature = "json")]
+    pub fn select(&self, value: &Value) -> Value {
+        let mut document = Value::Object(Map::new());
+        for path in &self.paths {
+            let selected = path
+                .split('.')
+                .try_fold(value, |value, name| value.get(name))
+                .cloned()
+                .unwrap_or(Value::Null);
+            let mut target = &mut document;
+            let mut names = path.split('.').peekable();
+            while let Some(name) = names.next() {
+                let Value::Object(members) = target else {
+                    break;
+                };
+                if names.peek().is_none() {
+                    members.insert(name.to_string(), selected);
+                    break;
+                }
+                target = members
+                    .entry(name)
+                    .or_insert_with(|| Value::Object(Map::new()));
+            }
+        }
+        document
+    }
+
+    /// Builds a patch from the masked fields of `value`, leaving the rest `Void`.
+    #[cfg(feature = "json")]
+    pub fn to_patch<T, P>(&self, value: &T) -> serde_json::Result<P>
+    where
+        T: Serialize,
+        P: DeserializeOwned,
+    {
+        merge_patch::from_document(self.select(&serde_json::to_value(value)?))
+    }
+}
+
+#[cfg(feature = "json")]
+fn push_paths(
+    paths: &mut Vec<String>,
+    parent: Option<&str>,
+    members: &Map<String, Value>,
+    tagged: bool,
+) {
+    for (name, value) in members {
+        let path = match parent {
+            Some(parent) => format!("{}.{}", parent, name),
+            None => name.clone(),
+        };
+        match nested_patch(value, tagged) {
+            Some(Value::Object(nested)) => push_paths(paths, Some(&path), nested, tagged),
+            _ => paths.push(path),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+    struct SettingsPatch {
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        theme: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        language: Maybe<String>,
+        #[serde(
+            default,
+            skip_serializing_if = "Maybe::is_void",
+            with = "crate::serde::nested"
+        )]
+        alerts: Maybe<AlertsPatch>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        extra: Maybe<Value>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+    struct AlertsPatch {
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        email: Maybe<bool>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        push: Maybe<bool>,
+    }
+
+    #[test]
+    pub fn it_builds_masks_from_patches() {
+        let patch = SettingsPatch {
+            language: Maybe::None,
+            alerts: Maybe::Some(AlertsPatch {
+                email: Maybe::Some(false),
+                push: Maybe::Void,
+            }),
+            extra: Maybe::Some(json!({"beta": true})),
+            ..Default::default()
+        };
+        assert_eq!(
+            FieldMask::from_patch(&patch).unwrap(),
+            FieldMask::new(["alerts.email", "extra", "language"])
+        );
+        assert_eq!(
+            FieldMask::from_patch(&SettingsPatch::default()).unwrap(),
+            FieldMask::default()
+        );
+    }
+
+    #[test]
+    pub fn it_leaves_out_empty_nested_patches() {
+        let patch = SettingsPatch {
+            theme: Maybe::Some("dark".into()),
+            alerts: Maybe::Some(AlertsPatch::default()),
+            ..Default::default()
+        };
+        assert_eq!(
+            FieldMask::from_patch(&patch).unwrap(),
+            FieldMask::new(["theme"])
+        );
+        assert_eq!(
+            FieldMask::from_document(&json!({"alerts": {}})),
+            FieldMask::default()
+        );
+    }
+
+    #[test]
+    pub fn it_builds_patches_from_masks() {
+        let settings = json!({"theme": "dark", "alerts": {"email": true, "push": false}});
+        let mask = FieldMask::new(["language", "alerts.push"]);
+        let patch: SettingsPatch = mask.to_patch(&settings).unwrap();
+        assert_eq!(
+            patch,
+            SettingsPatch {
+                language: Maybe::None,
+                alerts: Maybe::Some(AlertsPatch {
+                    email: Maybe::Void,
+                    push: Maybe::Some(false),
+                }),
+                ..Default::default()
+            }
+        );
+
+        let patch: SettingsPatch = FieldMask::new(["alerts"]).to_patch(&settings).unwrap();
+        assert_eq!(
+            patch.alerts,
+            Maybe::Some(AlertsPatch {
+                email: Maybe::Some(true),
+                push: Maybe::Some(false),
+            })
+        );
+    }
+
+    #[test]
+    pub fn it_reads_and_records_masked_fields() {
+        let mask = FieldMask::new(["name", "email"]);
+        assert_eq!(mask.masked("name", Some(1)), Maybe::Some(1));
+        assert_eq!(mask.masked::<i32>("email", None), Maybe::None);
+        assert_eq!(mask.masked("age", Some(1)), Maybe::Void);
+
+        let mut mask = FieldMask::default();
+        assert_eq!(mask.record("name", Maybe::Some(1)), Some(1));
+        assert_eq!(mask.record::<i32>("email", Maybe::None), None);
+        assert_eq!(mask.record::<i32>("age", Maybe::Void), None);
+        assert_eq!(mask, FieldMask::new(["name", "email"]));
+    }
+
+    #[test]
+    pub fn it_matches_parent_paths() {
+        let mask = FieldMask::new(["address", "name"]);
+        assert!(mask.contains("address.city"));
+        assert!(mask.contains("name"));
+        assert!(!mask.contains("names"));
+        assert!(!mask.contains("email"));
+    }
+}
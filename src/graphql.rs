@@ -0,0 +1,494 @@
+//! `async_graphql` support for `Maybe`.
+//!
+//! `Maybe` fields resolve to `null` when `None` or `Void`. Registering the [`OmitVoidFields`]
+//! extension on a schema leaves `Void` fields out of the response instead.
+
+use crate::{Maybe, Nullable, Omittable};
+use async_graphql::{
+    async_trait::async_trait,
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextPrepareRequest},
+    parser::types::Field,
+    registry, ContextSelectionSet, InputType, InputValueError, InputValueResult, MaybeUndefined,
+    OutputType, Positioned, Request, Response, ServerResult, Value,
+};
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex},
+};
+
+/// Strips the non-null marker from a qualified GraphQL type name, as `Maybe` values may always
+/// be omitted or null regardless of what `T` declares.
+fn nullable(type_name: String) -> String {
+    match type_name.strip_suffix('!') {
+        Some(stripped) => stripped.to_string(),
+        None => type_name,
+    }
+}
+
+impl<T> InputType for Maybe<T>
+where
+    T: InputType,
+{
+    type RawValueType = T::RawValueType;
+
+    fn type_name() -> Cow<'static, str> {
+        T::type_name()
+    }
+
+    fn qualified_type_name() -> String {
+        nullable(T::qualified_type_name())
+    }
+
+    fn create_type_info(registry: &mut registry::Registry) -> String {
+        nullable(T::create_type_info(registry))
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        match value {
+            None => Ok(Self::Void),
+            Some(Value::Null) => Ok(Self::None),
+            Some(value) => Ok(Self::Some(
+                T::parse(Some(value)).map_err(InputValueError::propagate)?,
+            )),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Self::Some(value) => value.to_value(),
+            _ => Value::Null,
+        }
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        if let Self::Some(value) = self {
+            value.as_raw_value()
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> From<MaybeUndefined<T>> for Maybe<T> {
+    fn from(value: MaybeUndefined<T>) -> Self {
+        match value {
+            MaybeUndefined::Undefined => Self::Void,
+            MaybeUndefined::Null => Self::None,
+            MaybeUndefined::Value(value) => Self::Some(value),
+        }
+    }
+}
+
+impl<T> From<Maybe<T>> for MaybeUndefined<T> {
+    fn from(value: Maybe<T>) -> Self {
+        match value {
+            Maybe::Void => Self::Undefined,
+            Maybe::None => Self::Null,
+            Maybe::Some(value) => Self::Value(value),
+        }
+    }
+}
+
+impl<T> OutputType for Maybe<T>
+where
+    T: OutputType + Sync,
+{
+    fn type_name() -> Cow<'static, str> {
+        T::type_name()
+    }
+
+    fn qualified_type_name() -> String {
+        nullable(T::qualified_type_name())
+    }
+
+    fn create_type_info(registry: &mut registry::Registry) -> String {
+        nullable(T::create_type_info(registry))
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ContextSelectionSet<'_>,
+        field: &Positioned<Field>,
+    ) -> ServerResult<Value> {
+        match self {
+            Self::Some(value) => match OutputType::resolve(value, ctx, field).await {
+                Ok(value) => Ok(value),
+                Err(err) => {
+                    ctx.add_error(err);
+                    Ok(Value::Null)
+                }
+            },
+            Self::None => Ok(Value::Null),
+            Self::Void => {
+                if let (Some(paths), Some(path)) = (ctx.data_opt::<VoidPaths>(), ctx.path_node) {
+                    paths.record(path.to_string_vec());
+                }
+                Ok(Value::Null)
+            }
+        }
+    }
+}
+
+/// Implements `InputType` and `OutputType` for a restricted variant of `Maybe` by going through
+/// `Maybe`, rejecting the forbidden state while parsing.
+macro_rules! restricted_graphql_type {
+    ($name:ident) => {
+        impl<T> InputType for $name<T>
+        where
+            T: InputType,
+        {
+            type RawValueType = T::RawValueType;
+
+            fn type_name() -> Cow<'static, str> {
+                Maybe::<T>::type_name()
+            }
+
+            fn qualified_type_name() -> String {
+                <Maybe<T> as InputType>::qualified_type_name()
+            }
+
+            fn create_type_info(registry: &mut registry::Registry) -> String {
+                <Maybe<T> as InputType>::create_type_info(registry)
+            }
+
+            fn parse(value: Option<Value>) -> InputValueResult<Self> {
+                let maybe = Maybe::<T>::parse(value).map_err(InputValueError::propagate)?;
+                maybe.try_into().map_err(InputValueError::custom)
+            }
+
+            fn to_value(&self) -> Value {
+                match self.as_maybe() {
+                    Maybe::Some(value) => value.to_value(),
+                    _ => Value::Null,
+                }
+            }
+
+            fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+                match self.as_maybe() {
+                    Maybe::Some(value) => value.as_raw_value(),
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T> OutputType for $name<T>
+        where
+            T: OutputType + Sync,
+        {
+            fn type_name() -> Cow<'static, str> {
+                T::type_name()
+            }
+
+            fn qualified_type_name() -> String {
+                <Maybe<T> as OutputType>::qualified_type_name()
+            }
+
+            fn create_type_info(registry: &mut registry::Registry) -> String {
+                <Maybe<T> as OutputType>::create_type_info(registry)
+            }
+
+            async fn resolve(
+                &self,
+                ctx: &ContextSelectionSet<'_>,
+                field: &Positioned<Field>,
+            ) -> ServerResult<Value> {
+                let maybe = self.as_maybe();
+                OutputType::resolve(&maybe, ctx, field).await
+            }
+        }
+    };
+}
+
+restricted_graphql_type!(Omittable);
+restricted_graphql_type!(Nullable);
+
+/// Schema extension which removes fields resolving to `Maybe::Void` from responses.
+///
+/// Without it, `Void` resolves to `null` just like `None`. `Void` list elements are always
+/// resolved to `null`, as removing them would shift the indices of the remaining elements.
+pub struct OmitVoidFields;
+
+impl ExtensionFactory for OmitVoidFields {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(OmitVoidFieldsExtension {
+            paths: VoidPaths::default(),
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+struct VoidPaths(Arc<Mutex<Vec<Vec<String>>>>);
+
+impl VoidPaths {
+    fn record(&self, path: Vec<String>) {
+        self.0.lock().unwrap().push(path);
+    }
+
+    fn take(&self) -> Vec<Vec<String>> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+struct OmitVoidFieldsExtension {
+    paths: VoidPaths,
+}
+
+#[async_trait]
+impl Extension for OmitVoidFieldsExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        next.run(ctx, request.data(self.paths.clone())).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let mut response = next.run(ctx, operation_name).await;
+        for path in self.paths.take() {
+            remove_path(&mut response.data, &path);
+        }
+        response
+    }
+}
+
+fn remove_path(value: &mut Value, path: &[String]) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = value;
+    for segment in parents {
+        current = match current {
+            Value::Object(map) => match map.get_mut(segment.as_str()) {
+                Some(value) => value,
+                None => return,
+            },
+            Value::List(list) => {
+                match segment.parse::<usize>().ok().and_then(|i| list.get_mut(i)) {
+                    Some(value) => value,
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+    }
+    if let Value::Object(map) = current {
+        map.shift_remove(last.as_str());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OmitVoidFields;
+    use crate::{Maybe, Nullable, Omittable};
+    use async_graphql::{
+        EmptyMutation, EmptySubscription, InputObject, MaybeUndefined, Object, Schema,
+    };
+
+    #[derive(InputObject)]
+    struct UserInput {
+        name: Maybe<String>,
+        age: Maybe<i32>,
+        tags: Maybe<Vec<String>>,
+        nickname: Maybe<Option<String>>,
+    }
+
+    #[derive(InputObject)]
+    struct LegacyUserInput {
+        name: MaybeUndefined<String>,
+        age: MaybeUndefined<i32>,
+    }
+
+    #[derive(Debug, PartialEq, Default)]
+    struct UserPatch {
+        name: Maybe<String>,
+        age: Maybe<i32>,
+        admin: Maybe<bool>,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn restricted(&self, omittable: Omittable<i32>, nullable: Nullable<i32>) -> i32 {
+            omittable.into_option().unwrap_or(0) + nullable.into_option().unwrap_or(0)
+        }
+
+        async fn echo(&self, value: Maybe<i32>) -> i32 {
+            value.unwrap_or(0)
+        }
+
+        async fn tags(&self, tags: Maybe<Vec<String>>) -> i32 {
+            tags.map_or(0, |tags| tags.len() as i32)
+        }
+
+        async fn update(&self, input: UserInput) -> bool {
+            input.name.is_defined()
+        }
+    }
+
+    fn sdl() -> String {
+        Schema::new(Query, EmptyMutation, EmptySubscription).sdl()
+    }
+
+    struct Profile;
+
+    #[Object]
+    impl Profile {
+        async fn name(&self) -> Maybe<String> {
+            Maybe::Some("Ferris".into())
+        }
+
+        async fn nickname(&self) -> Maybe<String> {
+            Maybe::None
+        }
+
+        async fn email(&self) -> Maybe<String> {
+            Maybe::Void
+        }
+
+        async fn scores(&self) -> Vec<Maybe<i32>> {
+            vec![Maybe::Some(1), Maybe::Void]
+        }
+    }
+
+    struct ProfileQuery;
+
+    #[Object]
+    impl ProfileQuery {
+        async fn profile(&self) -> Profile {
+            Profile
+        }
+    }
+
+    const PROFILE_QUERY: &str = "{ profile { name nickname email mail: email scores } }";
+
+    #[tokio::test]
+    pub async fn it_resolves_void_outputs_to_null_by_default() {
+        let schema = Schema::new(ProfileQuery, EmptyMutation, EmptySubscription);
+        let data = schema
+            .execute(PROFILE_QUERY)
+            .await
+            .into_result()
+            .unwrap()
+            .data;
+        assert_eq!(
+            data.into_json().unwrap(),
+            serde_json::json!({
+                "profile": {
+                    "name": "Ferris",
+                    "nickname": null,
+                    "email": null,
+                    "mail": null,
+                    "scores": [1, null],
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    pub async fn it_omits_void_outputs_with_the_extension() {
+        let schema = Schema::build(ProfileQuery, EmptyMutation, EmptySubscription)
+            .extension(OmitVoidFields)
+            .finish();
+        let data = schema
+            .execute(PROFILE_QUERY)
+            .await
+            .into_result()
+            .unwrap()
+            .data;
+        assert_eq!(
+            data.into_json().unwrap(),
+            serde_json::json!({
+                "profile": {
+                    "name": "Ferris",
+                    "nickname": null,
+                    "scores": [1, null],
+                }
+            })
+        );
+    }
+
+    #[test]
+    pub fn it_exports_maybe_outputs_as_nullable() {
+        let sdl = Schema::new(ProfileQuery, EmptyMutation, EmptySubscription).sdl();
+        assert!(sdl.contains("\tname: String\n"), "{}", sdl);
+        assert!(sdl.contains("\tscores: [Int]!\n"), "{}", sdl);
+    }
+
+    #[tokio::test]
+    pub async fn it_rejects_forbidden_restricted_inputs() {
+        let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+        let ok = schema.execute("{ restricted(nullable: null) }").await;
+        assert!(ok.errors.is_empty(), "{:?}", ok.errors);
+
+        let null = schema
+            .execute("{ restricted(omittable: null, nullable: 1) }")
+            .await;
+        assert_eq!(null.errors.len(), 1);
+        assert!(null.errors[0].message.contains("must not be null"));
+
+        let void = schema.execute("{ restricted(omittable: 1) }").await;
+        assert_eq!(void.errors.len(), 1);
+        assert!(void.errors[0].message.contains("must not be omitted"));
+    }
+
+    #[test]
+    pub fn it_converts_from_and_into_maybe_undefined() {
+        for (maybe, undefined) in [
+            (Maybe::Void, MaybeUndefined::Undefined),
+            (Maybe::None, MaybeUndefined::Null),
+            (Maybe::Some(1), MaybeUndefined::Value(1)),
+        ] {
+            assert_eq!(Maybe::from(undefined), maybe);
+            assert_eq!(MaybeUndefined::from(maybe), undefined);
+        }
+    }
+
+    #[test]
+    pub fn it_converts_input_objects_field_by_field() {
+        let input = LegacyUserInput {
+            name: MaybeUndefined::Null,
+            age: MaybeUndefined::Value(3),
+        };
+        let patch = crate::convert_fields!(input => UserPatch { name, age, ..Default::default() });
+        assert_eq!(
+            patch,
+            UserPatch {
+                name: Maybe::None,
+                age: Maybe::Some(3),
+                admin: Maybe::Void,
+            }
+        );
+        let patch = UserPatch {
+            name: Maybe::Void,
+            age: Maybe::Some(3),
+            admin: Maybe::None,
+        };
+        let input = crate::convert_fields!(patch => LegacyUserInput { name, age });
+        assert!(input.name.is_undefined());
+        assert_eq!(input.age, MaybeUndefined::Value(3));
+    }
+
+    #[test]
+    pub fn it_exports_maybe_arguments_as_nullable() {
+        let sdl = sdl();
+        assert!(sdl.contains("echo(value: Int): Int!"), "{}", sdl);
+        assert!(sdl.contains("tags(tags: [String!]): Int!"), "{}", sdl);
+    }
+
+    #[test]
+    pub fn it_exports_maybe_input_fields_as_nullable() {
+        let sdl = sdl();
+        assert!(sdl.contains("\tname: String\n"), "{}", sdl);
+        assert!(sdl.contains("\tage: Int\n"), "{}", sdl);
+        assert!(sdl.contains("\ttags: [String!]\n"), "{}", sdl);
+        assert!(sdl.contains("\tnickname: String\n"), "{}", sdl);
+    }
+}
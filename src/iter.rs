@@ -0,0 +1,353 @@
+//! Iterating over `Maybe` values and iterators of them.
+//!
+//! Like `Option`, a `Maybe` iterates over its `Some` value, if there is one. Collecting an
+//! iterator of `Maybe<T>` into a `Maybe<C>`, or extending a `Maybe<C>` with one, stops at the
+//! first `None` or `Void`, which becomes the result; [`Maybe::collect_with`] can skip one of
+//! them instead.
+
+use crate::Maybe;
+use std::{iter::FusedIterator, option};
+
+impl<T> Maybe<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.as_ref().into_option().into_iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.as_mut().into_option().into_iter(),
+        }
+    }
+}
+
+macro_rules! maybe_iter {
+    ($(#[$attr:meta])* $name:ident<$($lifetime:lifetime,)? $T:ident>, $item:ty) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub struct $name<$($lifetime,)? $T> {
+            inner: option::IntoIter<$item>,
+        }
+
+        impl<$($lifetime,)? $T> Iterator for $name<$($lifetime,)? $T> {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$($lifetime,)? $T> DoubleEndedIterator for $name<$($lifetime,)? $T> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back()
+            }
+        }
+
+        impl<$($lifetime,)? $T> ExactSizeIterator for $name<$($lifetime,)? $T> {}
+
+        impl<$($lifetime,)? $T> FusedIterator for $name<$($lifetime,)? $T> {}
+    };
+}
+
+maybe_iter!(
+    /// An iterator over a reference to the `Some` value of a `Maybe`.
+    Iter<'a, T>, &'a T
+);
+maybe_iter!(
+    /// An iterator over a mutable reference to the `Some` value of a `Maybe`.
+    IterMut<'a, T>, &'a mut T
+);
+maybe_iter!(
+    /// An iterator over the `Some` value of a `Maybe`.
+    #[derive(Clone)]
+    IntoIter<T>, T
+);
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> IntoIterator for Maybe<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            inner: self.into_option().into_iter(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Maybe<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Maybe<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// How [`Maybe::collect_with`] treats items which aren't `Some`.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum CollectMode {
+    /// Stop at the first `None` or `Void`, which becomes the result.
+    #[default]
+    StopAtFirst,
+    /// Skip `Void` items, stopping at the first `None`.
+    SkipVoid,
+    /// Skip `None` items, stopping at the first `Void`.
+    SkipNone,
+}
+
+impl<C> Maybe<C> {
+    /// Collects the `Some` values of `iter` into `C`, unless an item which isn't `Some` stops
+    /// the collection, as described by `mode`. Nothing after that item is consumed.
+    pub fn collect_with<I, T>(iter: I, mode: CollectMode) -> Self
+    where
+        I: IntoIterator<Item = Maybe<T>>,
+        C: FromIterator<T>,
+    {
+        let mut stopped_at = Maybe::Some(());
+        let items = iter.into_iter().filter(|item| match mode {
+            CollectMode::StopAtFirst => true,
+            CollectMode::SkipVoid => !item.is_void(),
+            CollectMode::SkipNone => !item.is_none(),
+        });
+        let collected = somes_until_empty(items, &mut stopped_at).collect();
+        stopped_at.and(Maybe::Some(collected))
+    }
+}
+
+/// Yields the `Some` values of `items` up to the first `None` or `Void`, which is recorded in
+/// `stopped_at`.
+fn somes_until_empty<'a, T>(
+    items: impl Iterator<Item = Maybe<T>> + 'a,
+    stopped_at: &'a mut Maybe<()>,
+) -> impl Iterator<Item = T> + 'a {
+    items.map_while(move |item| match item {
+        Maybe::Some(value) => Some(value),
+        item => {
+            *stopped_at = item.map(|_| ());
+            None
+        }
+    })
+}
+
+/// Collects the `Some` values, or returns the first `None` or `Void`.
+impl<T, C: FromIterator<T>> FromIterator<Maybe<T>> for Maybe<C> {
+    fn from_iter<I: IntoIterator<Item = Maybe<T>>>(iter: I) -> Self {
+        Self::collect_with(iter, CollectMode::StopAtFirst)
+    }
+}
+
+/// Extends a `Some` collection with the `Some` values, until the first `None` or `Void`, which
+/// replaces it. `None` and `Void` are left as they are, without consuming anything.
+impl<T, C: Extend<T>> Extend<Maybe<T>> for Maybe<C> {
+    fn extend<I: IntoIterator<Item = Maybe<T>>>(&mut self, iter: I) {
+        let Maybe::Some(collection) = self else {
+            return;
+        };
+        let mut stopped_at = Maybe::Some(());
+        collection.extend(somes_until_empty(iter.into_iter(), &mut stopped_at));
+        match stopped_at {
+            Maybe::Some(()) => {}
+            Maybe::None => *self = Maybe::None,
+            Maybe::Void => *self = Maybe::Void,
+        }
+    }
+}
+
+/// Adapters for iterators of `Maybe` values.
+pub trait MaybeIterator<T>: Iterator<Item = Maybe<T>> + Sized {
+    /// Yields the `Some` values, skipping `None` and `Void`.
+    fn somes(self) -> Somes<Self> {
+        Somes { iter: self }
+    }
+
+    /// Skips `Void` items.
+    fn defined(self) -> Defined<Self> {
+        Defined { iter: self }
+    }
+
+    /// Collects the `Some` values and counts the `None` and `Void` items. Those items hold no
+    /// value, so collecting them would give nothing more than their counts.
+    fn partition_maybe<C>(self) -> Partitioned<C>
+    where
+        C: Default + Extend<T>,
+    {
+        let mut partitioned = Partitioned {
+            somes: C::default(),
+            nones: 0,
+            voids: 0,
+        };
+        for item in self {
+            match item {
+                Maybe::Void => partitioned.voids += 1,
+                Maybe::None => partitioned.nones += 1,
+                Maybe::Some(value) => partitioned.somes.extend(Some(value)),
+            }
+        }
+        partitioned
+    }
+}
+
+impl<I, T> MaybeIterator<T> for I where I: Iterator<Item = Maybe<T>> {}
+
+#[derive(Debug, Clone)]
+pub struct Somes<I> {
+    iter: I,
+}
+
+impl<I, T> Iterator for Somes<I>
+where
+    I: Iterator<Item = Maybe<T>>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.by_ref().find_map(Maybe::into_option)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Defined<I> {
+    iter: I,
+}
+
+impl<I, T> Iterator for Defined<I>
+where
+    I: Iterator<Item = Maybe<T>>,
+{
+    type Item = Maybe<T>;
+
+    fn next(&mut self) -> Option<Maybe<T>> {
+        self.iter.by_ref().find(Maybe::is_defined)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// The result of [`MaybeIterator::partition_maybe`].
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Partitioned<C> {
+    pub somes: C,
+    pub nones: usize,
+    pub voids: usize,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn it_iterates_over_some() {
+        let mut value = Maybe::Some(1);
+        assert_eq!(value.iter().collect::<Vec<_>>(), vec![&1]);
+        for value in &mut value {
+            *value += 1;
+        }
+        assert_eq!(value.into_iter().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(Maybe::<i32>::None.iter().len(), 0);
+        assert_eq!(Maybe::<i32>::Void.into_iter().next(), None);
+    }
+
+    #[test]
+    pub fn it_collects_until_the_first_empty_item() {
+        let values = vec![Maybe::Some(1), Maybe::Some(2)];
+        assert_eq!(
+            values.into_iter().collect::<Maybe<Vec<_>>>(),
+            Maybe::Some(vec![1, 2])
+        );
+
+        let values = vec![Maybe::Some(1), Maybe::Void, Maybe::None];
+        assert_eq!(values.into_iter().collect::<Maybe<Vec<i32>>>(), Maybe::Void);
+
+        let values = vec![Maybe::Some(1), Maybe::None, Maybe::Void];
+        assert_eq!(values.into_iter().collect::<Maybe<Vec<i32>>>(), Maybe::None);
+    }
+
+    #[test]
+    pub fn it_stops_consuming_at_the_first_empty_item() {
+        let mut values = vec![Maybe::Some(1), Maybe::None, Maybe::Some(3)].into_iter();
+        assert_eq!(values.by_ref().collect::<Maybe<Vec<i32>>>(), Maybe::None);
+        assert_eq!(values.next(), Some(Maybe::Some(3)));
+    }
+
+    #[test]
+    pub fn it_extends_until_the_first_empty_item() {
+        let mut values = Maybe::Some(vec![1]);
+        values.extend([Maybe::Some(2), Maybe::Some(3)]);
+        assert_eq!(values, Maybe::Some(vec![1, 2, 3]));
+
+        let mut items = [Maybe::Some(4), Maybe::Void, Maybe::Some(5)].into_iter();
+        values.extend(items.by_ref());
+        assert_eq!(values, Maybe::Void);
+        assert_eq!(items.next(), Some(Maybe::Some(5)));
+
+        let mut values = Maybe::<Vec<i32>>::None;
+        values.extend([Maybe::Some(1)]);
+        assert_eq!(values, Maybe::None);
+    }
+
+    #[test]
+    pub fn it_collects_with_a_mode() {
+        let values = || vec![Maybe::Some(1), Maybe::Void, Maybe::Some(2), Maybe::None];
+        assert_eq!(
+            Maybe::<Vec<i32>>::collect_with(values(), CollectMode::SkipVoid),
+            Maybe::None
+        );
+        assert_eq!(
+            Maybe::<Vec<i32>>::collect_with(values(), CollectMode::SkipNone),
+            Maybe::Void
+        );
+        assert_eq!(
+            Maybe::<Vec<i32>>::collect_with(
+                vec![Maybe::Void, Maybe::Some(1), Maybe::Void],
+                CollectMode::SkipVoid
+            ),
+            Maybe::Some(vec![1])
+        );
+    }
+
+    #[test]
+    pub fn it_adapts_iterators() {
+        let values = || vec![Maybe::Some(1), Maybe::Void, Maybe::None, Maybe::Some(2)];
+        assert_eq!(values().into_iter().somes().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            values().into_iter().defined().collect::<Vec<_>>(),
+            vec![Maybe::Some(1), Maybe::None, Maybe::Some(2)]
+        );
+        assert_eq!(
+            values().into_iter().partition_maybe::<Vec<_>>(),
+            Partitioned {
+                somes: vec![1, 2],
+                nones: 1,
+                voids: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//! JSON Patch ([RFC 6902](https://www.rfc-editor.org/rfc/rfc6902)) operations generated from
+//! structs of `Maybe` fields.
+//!
+//! Patches are first serialized into a merge patch document (see [`crate::merge_patch`]), so
+//! `Void` fields produce no operation, `None` fields produce a `remove` (or a `replace` with
+//! `null`) and `Some` fields produce a `replace` (or an `add`). Fields marked with
+//! [`crate::serde::nested`] hold nested patches and produce operations for their own fields,
+//! while any other value is set whole, `null` members and all. [`from_merge_patch`] has no
+//! marks to go by, so it treats every object as a nested patch, as RFC 7396 does.
+//!
+//! Operations are sorted by path, so they don't depend on the order of fields, nor on whether
+//! `serde_json`'s `preserve_order` feature is enabled.
+
+use crate::{merge_patch, serde::nested};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    Add { path: String, value: Value },
+    Replace { path: String, value: Value },
+    Remove { path: String },
+}
+
+/// The operation emitted for `None` fields.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum NullOperation {
+    #[default]
+    Remove,
+    ReplaceWithNull,
+}
+
+/// The operation emitted for `Some` fields when the target document isn't known.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum SetOperation {
+    #[default]
+    Replace,
+    Add,
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct Options {
+    pub null: NullOperation,
+    pub set: SetOperation,
+}
+
+impl Operation {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Add { path, .. } | Self::Replace { path, .. } | Self::Remove { path } => path,
+        }
+    }
+}
+
+/// Generates the operations for a patch without looking at the document it will be applied to.
+pub fn operations<P>(patch: &P, options: Options) -> serde_json::Result<Vec<Operation>>
+where
+    P: Serialize,
+{
+    let document = nested::tagged(|| merge_patch::to_document(patch))?;
+    Ok(collect(&document, None, true, options))
+}
+
+/// Generates the operations for a patch which will be applied to `original`.
+///
+/// `Some` fields produce an `add` when the member is missing from `original` and a `replace`
+/// otherwise, and `None` fields for missing members produce nothing. Nested patches are only
+/// descended into where `original` holds an object, and are added whole elsewhere.
+pub fn operations_for<P>(
+    patch: &P,
+    original: &Value,
+    options: Options,
+) -> serde_json::Result<Vec<Operation>>
+where
+    P: Serialize,
+{
+    let document = nested::tagged(|| merge_patch::to_document(patch))?;
+    Ok(collect(&document, Some(original), true, options))
+}
+
+/// Converts a merge patch document into equivalent operations.
+pub fn from_merge_patch(document: &Value, options: Options) -> Vec<Operation> {
+    collect(document, None, false, options)
+}
+
+/// Generates the operations for `document`, where `tagged` means that nested patches are
+/// tagged by [`nested::tagged`] rather than being every object.
+fn collect(
+    document: &Value,
+    original: Option<&Value>,
+    tagged: bool,
+    options: Options,
+) -> Vec<Operation> {
+    let mut operations = Vec::new();
+    push_operations(&mut operations, "", document, original, tagged, options);
+    operations.sort_by(|lhs, rhs| lhs.path().cmp(rhs.path()));
+    operations
+}
+
+fn push_operations(
+    operations: &mut Vec<Operation>,
+    path: &str,
+    document: &Value,
+    original: Option<&Value>,
+    tagged: bool,
+    options: Options,
+) {
+    let members = match (document, original) {
+        (Value::Object(members), None | Some(Value::Object(_))) => members,
+        (document, original) => {
+            let value = whole_patch(document, tagged);
+            operations.push(set_operation(path, value, original.map(|_| true), options));
+            return;
+        }
+    };
+    for (name, value) in members {
+        let path = format!("{}/{}", path, escape(name));
+        let existing = original.map(|original| original.get(name));
+        match (value, nested_patch(value, tagged), existing) {
+            (Value::Null, _, Some(None)) => {}
+            (Value::Null, _, _) => operations.push(match options.null {
+                NullOperation::Remove => Operation::Remove { path },
+                NullOperation::ReplaceWithNull => Operation::Replace {
+                    path,
+                    value: Value::Null,
+                },
+            }),
+            (_, Some(patch), None) => {
+                push_operations(operations, &path, patch, None, tagged, options)
+            }
+            (_, Some(patch), Some(Some(existing))) => {
+                push_operations(operations, &path, patch, Some(existing), tagged, options)
+            }
+            (_, Some(patch), Some(None)) => operations.push(set_operation(
+                &path,
+                whole_patch(patch, tagged),
+                Some(false),
+                options,
+            )),
+            (value, None, existing) => operations.push(set_operation(
+                &path,
+                untag(value),
+                existing.map(|existing| existing.is_some()),
+                options,
+            )),
+        }
+    }
+}
+
+/// Returns the nested patch held by a member: the tagged value of a marked field if `tagged`,
+/// and any object otherwise.
+pub(crate) fn nested_patch(value: &Value, tagged: bool) -> Option<&Value> {
+    match value {
+        Value::Object(members) if tagged => {
+            nested::tagged_value(members.len(), members.iter().next())
+        }
+        Value::Object(_) => Some(value),
+        _ => None,
+    }
+}
+
+/// Returns the value a nested patch sets when there is nothing to apply it to, which leaves out
+/// its `null` members.
+fn whole_patch(patch: &Value, tagged: bool) -> Value {
+    let Value::Object(members) = patch else {
+        return untag(patch);
+    };
+    let members = members
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(name, value)| {
+            let value = match nested_patch(value, tagged) {
+                Some(patch) => whole_patch(patch, tagged),
+                None => untag(value),
+            };
+            (name.clone(), value)
+        });
+    Value::Object(members.collect())
+}
+
+/// Removes the tags of marked fields from a value which is set whole.
+fn untag(value: &Value) -> Value {
+    match value {
+        Value::Object(members) => match nested_patch(value, true) {
+            Some(patch) => untag(patch),
+            None => Value::Object(
+                members
+                    .iter()
+                    .map(|(name, value)| (name.clone(), untag(value)))
+                    .collect(),
+            ),
+        },
+        Value::Array(items) => Value::Array(items.iter().map(untag).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Picks between `add` and `replace`, based on whether the member exists in the original
+/// document if it is known, and on `options` otherwise.
+fn set_operation(path: &str, value: Value, exists: Option<bool>, options: Options) -> Operation {
+    let path = path.to_string();
+    let replace = match exists {
+        Some(exists) => exists,
+        None => options.set == SetOperation::Replace,
+    };
+    if replace {
+        Operation::Replace { path, value }
+    } else {
+        Operation::Add { path, value }
+    }
+}
+
+/// Escapes a member name for use in a JSON Pointer.
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Maybe;
+    use serde_json::json;
+
+    #[derive(Serialize, Default)]
+    struct ArticlePatch {
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        title: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        summary: Maybe<String>,
+        #[serde(
+            default,
+            skip_serializing_if = "Maybe::is_void",
+            with = "crate::serde::nested",
+            rename = "meta/data"
+        )]
+        metadata: Maybe<MetadataPatch>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        author: Maybe<Author>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        extra: Maybe<Value>,
+    }
+
+    #[derive(Serialize, Default)]
+    struct MetadataPatch {
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        license: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        source: Maybe<String>,
+    }
+
+    #[derive(Serialize)]
+    struct Author {
+        name: String,
+        email: Option<String>,
+    }
+
+    fn patch() -> ArticlePatch {
+        ArticlePatch {
+            title: Maybe::Some("Maybe".into()),
+            summary: Maybe::None,
+            metadata: Maybe::Some(MetadataPatch {
+                license: Maybe::Void,
+                source: Maybe::None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn it_generates_operations_with_nested_paths() {
+        let operations = operations(&patch(), Options::default()).unwrap();
+        assert_eq!(
+            serde_json::to_value(operations).unwrap(),
+            json!([
+                {"op": "remove", "path": "/meta~1data/source"},
+                {"op": "remove", "path": "/summary"},
+                {"op": "replace", "path": "/title", "value": "Maybe"},
+            ])
+        );
+    }
+
+    #[test]
+    pub fn it_emits_nothing_for_void_patches() {
+        assert!(operations(&ArticlePatch::default(), Options::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    pub fn it_sets_values_which_are_not_nested_patches_whole() {
+        let patch = ArticlePatch {
+            author: Maybe::Some(Author {
+                name: "Ada".into(),
+                email: None,
+            }),
+            extra: Maybe::Some(json!({})),
+            ..Default::default()
+        };
+        assert_eq!(
+            operations(&patch, Options::default()).unwrap(),
+            vec![
+                Operation::Replace {
+                    path: "/author".into(),
+                    value: json!({"name": "Ada", "email": null}),
+                },
+                Operation::Replace {
+                    path: "/extra".into(),
+                    value: json!({}),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn it_sets_marked_values_which_are_not_objects_whole() {
+        #[derive(Serialize)]
+        struct NicknamePatch {
+            #[serde(with = "crate::serde::nested")]
+            nickname: Maybe<String>,
+        }
+
+        let patch = NicknamePatch {
+            nickname: Maybe::Some("Ferris".into()),
+        };
+        assert_eq!(
+            operations(&patch, Options::default()).unwrap(),
+            vec![Operation::Replace {
+                path: "/nickname".into(),
+                value: json!("Ferris"),
+            }]
+        );
+    }
+
+    #[test]
+    pub fn it_treats_every_object_in_a_merge_patch_as_nested() {
+        let document = json!({"author": {"email": null}, "extra": {}});
+        assert_eq!(
+            from_merge_patch(&document, Options::default()),
+            vec![Operation::Remove {
+                path: "/author/email".into(),
+            }]
+        );
+    }
+
+    #[test]
+    pub fn it_applies_the_configured_operations() {
+        let options = Options {
+            null: NullOperation::ReplaceWithNull,
+            set: SetOperation::Add,
+        };
+        let operations = operations(&patch(), options).unwrap();
+        assert_eq!(
+            operations,
+            vec![
+                Operation::Replace {
+                    path: "/meta~1data/source".into(),
+                    value: Value::Null,
+                },
+                Operation::Replace {
+                    path: "/summary".into(),
+                    value: Value::Null,
+                },
+                Operation::Add {
+                    path: "/title".into(),
+                    value: json!("Maybe"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn it_picks_operations_from_the_original_document() {
+        let patch = ArticlePatch {
+            title: Maybe::Some("Maybe".into()),
+            summary: Maybe::None,
+            metadata: Maybe::Some(MetadataPatch {
+                license: Maybe::Some("MIT".into()),
+                source: Maybe::None,
+            }),
+            ..Default::default()
+        };
+        let original = json!({"summary": "Three states"});
+        assert_eq!(
+            operations_for(&patch, &original, Options::default()).unwrap(),
+            vec![
+                Operation::Add {
+                    path: "/meta~1data".into(),
+                    value: json!({"license": "MIT"}),
+                },
+                Operation::Remove {
+                    path: "/summary".into(),
+                },
+                Operation::Add {
+                    path: "/title".into(),
+                    value: json!("Maybe"),
+                },
+            ]
+        );
+    }
+}
@@ -1,9 +1,53 @@
+extern crate self as maybe;
+
+#[cfg(feature = "sea_orm")]
+mod active_value;
+mod arithmetic;
+#[cfg(feature = "bson")]
+pub mod bson;
+mod diff;
+pub mod field_mask;
 #[cfg(feature = "async_graphql")]
-use async_graphql::{registry, InputType, InputValueError, InputValueResult, Value};
+pub mod graphql;
+pub mod iter;
+#[cfg(feature = "json")]
+pub mod json_patch;
+mod logic;
+pub mod merge;
+#[cfg(feature = "json")]
+pub mod merge_patch;
+mod patch;
+#[cfg(feature = "prost")]
+pub mod protobuf;
+mod restricted;
 #[cfg(feature = "serde")]
-use serde::{ser::Error as SerError, Deserialize, Deserializer, Serialize, Serializer};
-#[cfg(feature = "async_graphql")]
-use std::borrow::Cow;
+pub mod serde;
+pub mod sql;
+
+pub use diff::Diff;
+pub use iter::MaybeIterator;
+pub use patch::{Compose, Invert, Patch, PatchError};
+pub use restricted::{Nullable, Omittable, RestrictionError};
+
+#[cfg(all(feature = "derive", feature = "diesel"))]
+pub use maybe_derive::AsChangeset;
+#[cfg(feature = "derive")]
+pub use maybe_derive::{serde_fields, Diff, Patch};
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "diesel")]
+    pub use ::diesel;
+    #[cfg(feature = "serde")]
+    pub use ::serde;
+}
+
+#[cfg(feature = "serde")]
+use ::serde::{ser::Error as SerError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    mem,
+    ops::{Deref, DerefMut},
+};
 
 #[derive(Debug, Default, Eq, PartialEq)]
 pub enum Maybe<T> {
@@ -25,109 +69,463 @@ impl<T> Maybe<T> {
     pub fn is_some(&self) -> bool {
         matches!(self, Self::Some(_))
     }
-}
 
-impl<T> Clone for Maybe<T>
-where
-    T: Clone,
-{
-    fn clone(&self) -> Self {
+    /// Returns `true` for `None` and `Some`, i.e. whenever a value was provided at all.
+    pub fn is_defined(&self) -> bool {
+        !self.is_void()
+    }
+
+    /// Returns `true` if the value is `Some` and the predicate holds, never for `Void` or `None`.
+    pub fn is_some_and(self, f: impl FnOnce(T) -> bool) -> bool {
         match self {
-            Self::Void => Self::Void,
-            Self::None => Self::None,
-            Self::Some(value) => Self::Some(value.clone()),
+            Self::Some(value) => f(value),
+            _ => false,
         }
     }
-}
 
-impl<T> Copy for Maybe<T> where T: Copy {}
+    /// Returns `true` if the value is `Void` or `None`, or if the predicate holds for `Some`.
+    pub fn is_none_or(self, f: impl FnOnce(T) -> bool) -> bool {
+        match self {
+            Self::Some(value) => f(value),
+            _ => true,
+        }
+    }
 
-impl<T> From<Option<T>> for Maybe<T> {
-    fn from(option: Option<T>) -> Maybe<T> {
-        match option {
-            Some(value) => Maybe::Some(value),
-            None => Maybe::None,
+    pub fn as_ref(&self) -> Maybe<&T> {
+        match self {
+            Self::Void => Maybe::Void,
+            Self::None => Maybe::None,
+            Self::Some(value) => Maybe::Some(value),
         }
     }
-}
 
-impl<T> From<Maybe<T>> for Option<T> {
-    fn from(maybe: Maybe<T>) -> Self {
-        match maybe {
-            Maybe::Some(value) => Some(value),
-            _ => None,
+    pub fn as_mut(&mut self) -> Maybe<&mut T> {
+        match self {
+            Self::Void => Maybe::Void,
+            Self::None => Maybe::None,
+            Self::Some(value) => Maybe::Some(value),
+        }
+    }
+
+    pub fn as_deref(&self) -> Maybe<&T::Target>
+    where
+        T: Deref,
+    {
+        self.as_ref().map(|value| value.deref())
+    }
+
+    pub fn as_deref_mut(&mut self) -> Maybe<&mut T::Target>
+    where
+        T: DerefMut,
+    {
+        self.as_mut().map(|value| value.deref_mut())
+    }
+
+    pub fn expect(self, message: &str) -> T {
+        match self {
+            Self::Some(value) => value,
+            _ => panic!("{}", message),
+        }
+    }
+
+    pub fn unwrap(self) -> T {
+        match self {
+            Self::Some(value) => value,
+            Self::None => panic!("called `Maybe::unwrap()` on a `None` value"),
+            Self::Void => panic!("called `Maybe::unwrap()` on a `Void` value"),
         }
     }
+
+    /// Returns the `Some` value, or `default` for both `Void` and `None`.
+    pub fn unwrap_or(self, default: T) -> T {
+        match self {
+            Self::Some(value) => value,
+            _ => default,
+        }
+    }
+
+    /// Returns the `Some` value, or `f()` for both `Void` and `None`.
+    pub fn unwrap_or_else(self, f: impl FnOnce() -> T) -> T {
+        match self {
+            Self::Some(value) => value,
+            _ => f(),
+        }
+    }
+
+    /// Returns the `Some` value, or `T::default()` for both `Void` and `None`.
+    pub fn unwrap_or_default(self) -> T
+    where
+        T: Default,
+    {
+        self.unwrap_or_else(T::default)
+    }
+
+    /// Maps the contained value, leaving `Void` and `None` as they are.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Maybe<U> {
+        match self {
+            Self::Void => Maybe::Void,
+            Self::None => Maybe::None,
+            Self::Some(value) => Maybe::Some(f(value)),
+        }
+    }
+
+    /// Replaces `Void` with `Some(f())`, leaving `None` and `Some` as they are.
+    pub fn map_void(self, f: impl FnOnce() -> T) -> Self {
+        match self {
+            Self::Void => Self::Some(f()),
+            other => other,
+        }
+    }
+
+    /// Replaces `None` with `Some(f())`, leaving `Void` and `Some` as they are.
+    pub fn map_none(self, f: impl FnOnce() -> T) -> Self {
+        match self {
+            Self::None => Self::Some(f()),
+            other => other,
+        }
+    }
+
+    pub fn inspect(self, f: impl FnOnce(&T)) -> Self {
+        if let Self::Some(value) = &self {
+            f(value);
+        }
+        self
+    }
+
+    pub fn map_or<U>(self, default: U, f: impl FnOnce(T) -> U) -> U {
+        match self {
+            Self::Some(value) => f(value),
+            _ => default,
+        }
+    }
+
+    pub fn map_or_else<U>(self, default: impl FnOnce() -> U, f: impl FnOnce(T) -> U) -> U {
+        match self {
+            Self::Some(value) => f(value),
+            _ => default(),
+        }
+    }
+
+    /// Converts `Some` into `Ok`, and both `Void` and `None` into `Err(err)`.
+    pub fn ok_or<E>(self, err: E) -> Result<T, E> {
+        match self {
+            Self::Some(value) => Ok(value),
+            _ => Err(err),
+        }
+    }
+
+    /// Converts `Some` into `Ok`, and both `Void` and `None` into `Err(err())`.
+    pub fn ok_or_else<E>(self, err: impl FnOnce() -> E) -> Result<T, E> {
+        match self {
+            Self::Some(value) => Ok(value),
+            _ => Err(err()),
+        }
+    }
+
+    /// Collapses `Void` and `None` into `Option::None`.
+    pub fn into_option(self) -> Option<T> {
+        self.into()
+    }
+
+    /// Returns `other` if `self` is `Some`, otherwise keeps `self` (`Void` or `None`).
+    pub fn and<U>(self, other: Maybe<U>) -> Maybe<U> {
+        self.and_then(|_| other)
+    }
+
+    /// Calls `f` with the contained value, leaving `Void` and `None` as they are.
+    pub fn and_then<U>(self, f: impl FnOnce(T) -> Maybe<U>) -> Maybe<U> {
+        match self {
+            Self::Void => Maybe::Void,
+            Self::None => Maybe::None,
+            Self::Some(value) => f(value),
+        }
+    }
+
+    /// Turns `Some` into `None` if the predicate does not hold. `Void` stays `Void`.
+    pub fn filter(self, predicate: impl FnOnce(&T) -> bool) -> Self {
+        match self {
+            Self::Some(value) if !predicate(&value) => Self::None,
+            other => other,
+        }
+    }
+
+    /// Returns `self` if it is `Some`, otherwise `other`. Both `Void` and `None` are replaced.
+    pub fn or(self, other: Self) -> Self {
+        self.or_else(|| other)
+    }
+
+    /// Returns `self` if it is `Some`, otherwise `f()`. Both `Void` and `None` are replaced.
+    pub fn or_else(self, f: impl FnOnce() -> Self) -> Self {
+        match self {
+            Self::Some(value) => Self::Some(value),
+            _ => f(),
+        }
+    }
+
+    /// Returns `other` if `self` is `Void`, otherwise `self`. `None` is kept.
+    pub fn or_void(self, other: Self) -> Self {
+        self.or_void_else(|| other)
+    }
+
+    /// Returns `f()` if `self` is `Void`, otherwise `self`. `None` is kept.
+    pub fn or_void_else(self, f: impl FnOnce() -> Self) -> Self {
+        match self {
+            Self::Void => f(),
+            other => other,
+        }
+    }
+
+    /// Returns `other` if `self` is `None`, otherwise `self`. `Void` is kept.
+    pub fn or_none(self, other: Self) -> Self {
+        self.or_none_else(|| other)
+    }
+
+    /// Returns `f()` if `self` is `None`, otherwise `self`. `Void` is kept.
+    pub fn or_none_else(self, f: impl FnOnce() -> Self) -> Self {
+        match self {
+            Self::None => f(),
+            other => other,
+        }
+    }
+
+    /// Returns the `Some` value if exactly one side is `Some`.
+    ///
+    /// Otherwise returns `Void` if both sides are `Void`, and `None` in every other case.
+    pub fn xor(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Some(value), Self::Void | Self::None) => Self::Some(value),
+            (Self::Void | Self::None, Self::Some(value)) => Self::Some(value),
+            (Self::Void, Self::Void) => Self::Void,
+            _ => Self::None,
+        }
+    }
+
+    /// Zips two `Some` values. Otherwise returns the first of `self` or `other` that isn't `Some`.
+    pub fn zip<U>(self, other: Maybe<U>) -> Maybe<(T, U)> {
+        self.and_then(|a| other.map(|b| (a, b)))
+    }
+
+    pub fn zip_with<U, R>(self, other: Maybe<U>, f: impl FnOnce(T, U) -> R) -> Maybe<R> {
+        self.zip(other).map(|(a, b)| f(a, b))
+    }
+
+    pub fn insert(&mut self, value: T) -> &mut T {
+        *self = Self::Some(value);
+        match self {
+            Self::Some(value) => value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the `Some` value, first replacing `Void` or `None` with `Some(value)`.
+    pub fn get_or_insert(&mut self, value: T) -> &mut T {
+        self.get_or_insert_with(|| value)
+    }
+
+    /// Returns the `Some` value, first replacing `Void` or `None` with `Some(f())`.
+    pub fn get_or_insert_with(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        if !self.is_some() {
+            *self = Self::Some(f());
+        }
+        match self {
+            Self::Some(value) => value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Takes the value out, leaving `Void` in its place.
+    pub fn take(&mut self) -> Self {
+        mem::take(self)
+    }
+
+    /// Takes the `Some` value out if the predicate holds, leaving `Void` in its place. Returns
+    /// `Void`, not `None`, if nothing was taken, and never takes a `None`.
+    pub fn take_if(&mut self, predicate: impl FnOnce(&mut T) -> bool) -> Self {
+        if self.as_mut().is_some_and(predicate) {
+            self.take()
+        } else {
+            Self::Void
+        }
+    }
+
+    pub fn replace(&mut self, value: T) -> Self {
+        mem::replace(self, Self::Some(value))
+    }
 }
 
-/*
-impl<T> From<Maybe<T>> for Option<Option<T>> {
-    fn from(maybe_undefined: Maybe<T>) -> Self {
-        match maybe_undefined {
-            Maybe::Void => None,
-            Maybe::None => Some(None),
-            Maybe::Some(value) => Some(Some(value)),
+impl<T, U> Maybe<(T, U)> {
+    pub fn unzip(self) -> (Maybe<T>, Maybe<U>) {
+        match self {
+            Self::Void => (Maybe::Void, Maybe::Void),
+            Self::None => (Maybe::None, Maybe::None),
+            Self::Some((a, b)) => (Maybe::Some(a), Maybe::Some(b)),
         }
     }
 }
 
-impl<T> From<Option<Option<T>>> for Maybe<T> {
-    fn from(value: Option<Option<T>>) -> Self {
+impl<T> Maybe<Maybe<T>> {
+    /// Removes one level of nesting. An outer `Void` or `None` is kept as is.
+    pub fn flatten(self) -> Maybe<T> {
+        self.and_then(|inner| inner)
+    }
+}
+
+impl<T> Maybe<&T> {
+    pub fn copied(self) -> Maybe<T>
+    where
+        T: Copy,
+    {
+        self.map(|value| *value)
+    }
+
+    pub fn cloned(self) -> Maybe<T>
+    where
+        T: Clone,
+    {
+        self.map(T::clone)
+    }
+}
+
+impl<T> Maybe<&mut T> {
+    pub fn copied(self) -> Maybe<T>
+    where
+        T: Copy,
+    {
+        self.map(|value| *value)
+    }
+
+    pub fn cloned(self) -> Maybe<T>
+    where
+        T: Clone,
+    {
+        self.map(|value| value.clone())
+    }
+}
+
+impl<T> Maybe<T> {
+    /// Converts into the double option shape used by e.g. `serde_with::rust::double_option`.
+    ///
+    /// `Void` becomes `None`, `None` becomes `Some(None)` and `Some(v)` becomes `Some(Some(v))`.
+    /// These are inherent methods rather than `From` impls, as the latter would make
+    /// conversions from a plain `Option` ambiguous.
+    pub fn into_double_option(self) -> Option<Option<T>> {
+        match self {
+            Self::Void => None,
+            Self::None => Some(None),
+            Self::Some(value) => Some(Some(value)),
+        }
+    }
+
+    /// The inverse of [`Maybe::into_double_option`].
+    pub fn from_double_option(value: Option<Option<T>>) -> Self {
         match value {
             Some(Some(value)) => Self::Some(value),
             Some(None) => Self::None,
             None => Self::Void,
         }
     }
-}
-*/
 
-#[cfg(feature = "async_graphql")]
-impl<T> InputType for Maybe<T>
-where
-    T: InputType,
-{
-    type RawValueType = T::RawValueType;
+    /// Combines two sequential edits of a value into one. `later` wins unless it is `Void`.
+    pub fn compose(earlier: Self, later: Self) -> Self {
+        later.or_void(earlier)
+    }
 
-    fn type_name() -> Cow<'static, str> {
-        T::type_name()
+    /// Returns the edit which undoes `self` when applied after it, given the `original` value
+    /// (`None` when null). `Void` stays `Void`.
+    pub fn invert(&self, original: Option<&T>) -> Self
+    where
+        T: Clone,
+    {
+        match (self, original) {
+            (Self::Void, _) => Self::Void,
+            (_, None) => Self::None,
+            (_, Some(value)) => Self::Some(value.clone()),
+        }
     }
+}
 
-    fn qualified_type_name() -> String {
-        T::type_name().to_string()
+impl<T> Maybe<Option<T>> {
+    /// Removes the inner `Option`, turning `Some(None)` into `None`.
+    pub fn flatten_option(self) -> Maybe<T> {
+        self.and_then(Maybe::from)
     }
 
-    fn create_type_info(registry: &mut registry::Registry) -> String {
-        T::create_type_info(registry);
-        T::type_name().to_string()
+    /// Converts into an `Option` of `Maybe`, with `Some(None)` mapping to `None`.
+    pub fn transpose(self) -> Option<Maybe<T>> {
+        match self {
+            Self::Void => Some(Maybe::Void),
+            Self::None => Some(Maybe::None),
+            Self::Some(Some(value)) => Some(Maybe::Some(value)),
+            Self::Some(None) => None,
+        }
     }
+}
 
-    fn parse(value: Option<Value>) -> InputValueResult<Self> {
-        match value {
-            None => Ok(Self::Void),
-            Some(Value::Null) => Ok(Self::None),
-            Some(value) => Ok(Self::Some(
-                T::parse(Some(value)).map_err(InputValueError::propagate)?,
-            )),
+impl<T, E> Maybe<Result<T, E>> {
+    pub fn transpose(self) -> Result<Maybe<T>, E> {
+        match self {
+            Self::Void => Ok(Maybe::Void),
+            Self::None => Ok(Maybe::None),
+            Self::Some(result) => result.map(Maybe::Some),
         }
     }
+}
 
-    fn to_value(&self) -> Value {
+impl<T> Clone for Maybe<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
         match self {
-            Self::Some(value) => value.to_value(),
-            _ => Value::Null,
+            Self::Void => Self::Void,
+            Self::None => Self::None,
+            Self::Some(value) => Self::Some(value.clone()),
         }
     }
+}
 
-    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
-        if let Self::Some(value) = self {
-            value.as_raw_value()
-        } else {
-            None
+impl<T> Copy for Maybe<T> where T: Copy {}
+
+impl<T> From<Option<T>> for Maybe<T> {
+    fn from(option: Option<T>) -> Maybe<T> {
+        match option {
+            Some(value) => Maybe::Some(value),
+            None => Maybe::None,
+        }
+    }
+}
+
+impl<T> From<Maybe<T>> for Option<T> {
+    fn from(maybe: Maybe<T>) -> Self {
+        match maybe {
+            Maybe::Some(value) => Some(value),
+            _ => None,
         }
     }
 }
 
+/// Builds a struct by converting each listed field of `source` with `From`.
+///
+/// Useful for moving between input types which use another tri-state type (such as
+/// `async_graphql::MaybeUndefined`) and structs of `Maybe` fields.
+///
+/// ```ignore
+/// let patch = maybe::convert_fields!(input => UserPatch { name, email, ..Default::default() });
+/// ```
+#[macro_export]
+macro_rules! convert_fields {
+    ($source:expr => $target:ident { $($field:ident),* $(,)? }) => {
+        $crate::convert_fields!($source => $target { $($field),* , ..})
+    };
+    ($source:expr => $target:ident { $($field:ident),* , .. $($rest:expr)? }) => {{
+        let source = $source;
+        $target {
+            $($field: ::core::convert::From::from(source.$field),)*
+            $(..$rest)?
+        }
+    }};
+}
+
 #[cfg(feature = "serde")]
 impl<'de, T> Deserialize<'de> for Maybe<T>
 where
@@ -149,6 +547,7 @@ impl<T: Serialize> Serialize for Maybe<T> {
     {
         let error_message = r#"'Maybe' fields need to be annotated with:
         #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        or the struct with #[maybe::serde_fields]
         "#;
         match self {
             Maybe::Some(value) => value.serialize(serializer),
@@ -159,6 +558,144 @@ impl<T: Serialize> Serialize for Maybe<T> {
     }
 }
 
+#[cfg(test)]
+mod combinator_test {
+    use super::*;
+
+    #[test]
+    pub fn it_maps_only_some_values() {
+        assert_eq!(Maybe::Some(2).map(|x| x * 2), Maybe::Some(4));
+        assert_eq!(Maybe::<i32>::None.map(|x| x * 2), Maybe::None);
+        assert_eq!(Maybe::<i32>::Void.map(|x| x * 2), Maybe::Void);
+    }
+
+    #[test]
+    pub fn it_maps_only_the_matching_empty_state() {
+        assert_eq!(Maybe::Void.map_void(|| 1), Maybe::Some(1));
+        assert_eq!(Maybe::None.map_void(|| 1), Maybe::None);
+        assert_eq!(Maybe::Some(2).map_void(|| 1), Maybe::Some(2));
+
+        assert_eq!(Maybe::None.map_none(|| 1), Maybe::Some(1));
+        assert_eq!(Maybe::Void.map_none(|| 1), Maybe::Void);
+        assert_eq!(Maybe::Some(2).map_none(|| 1), Maybe::Some(2));
+    }
+
+    #[test]
+    pub fn it_keeps_the_empty_state_through_and_then() {
+        let half = |x: i32| {
+            if x % 2 == 0 {
+                Maybe::Some(x / 2)
+            } else {
+                Maybe::None
+            }
+        };
+        assert_eq!(Maybe::Some(4).and_then(half), Maybe::Some(2));
+        assert_eq!(Maybe::Some(3).and_then(half), Maybe::None);
+        assert_eq!(Maybe::Void.and_then(half), Maybe::Void);
+        assert_eq!(Maybe::None.and_then(half), Maybe::None);
+    }
+
+    #[test]
+    pub fn it_filters_some_into_none() {
+        assert_eq!(Maybe::Some(3).filter(|x| *x > 5), Maybe::None);
+        assert_eq!(Maybe::Some(8).filter(|x| *x > 5), Maybe::Some(8));
+        assert_eq!(Maybe::<i32>::Void.filter(|x| *x > 5), Maybe::Void);
+    }
+
+    #[test]
+    pub fn it_distinguishes_or_from_or_void_and_or_none() {
+        assert_eq!(Maybe::Void.or(Maybe::Some(1)), Maybe::Some(1));
+        assert_eq!(Maybe::None.or(Maybe::Some(1)), Maybe::Some(1));
+        assert_eq!(Maybe::Void.or_void(Maybe::Some(1)), Maybe::Some(1));
+        assert_eq!(Maybe::None.or_void(Maybe::Some(1)), Maybe::None);
+        assert_eq!(Maybe::None.or_none(Maybe::Some(1)), Maybe::Some(1));
+        assert_eq!(Maybe::Void.or_none(Maybe::Some(1)), Maybe::Void);
+        assert_eq!(Maybe::Some(2).or_void(Maybe::Some(1)), Maybe::Some(2));
+    }
+
+    #[test]
+    pub fn it_unwraps_with_defaults() {
+        assert_eq!(Maybe::Some(1).unwrap_or(0), 1);
+        assert_eq!(Maybe::None.unwrap_or(0), 0);
+        assert_eq!(Maybe::<i32>::Void.unwrap_or_default(), 0);
+        assert_eq!(Maybe::Void.map_or(7, |x: i32| x + 1), 7);
+        assert!(Maybe::Some(3).is_some_and(|x| x == 3));
+        assert!(Maybe::<i32>::Void.is_none_or(|x| x == 3));
+    }
+
+    #[test]
+    pub fn it_zips_and_xors() {
+        assert_eq!(Maybe::Some(1).zip(Maybe::Some("a")), Maybe::Some((1, "a")));
+        assert_eq!(Maybe::Some(1).zip(Maybe::<i32>::Void), Maybe::Void);
+        assert_eq!(Maybe::<i32>::None.zip(Maybe::<i32>::Void), Maybe::None);
+        assert_eq!(Maybe::Some(1).xor(Maybe::Void), Maybe::Some(1));
+        assert_eq!(Maybe::Some(1).xor(Maybe::Some(2)), Maybe::None);
+        assert_eq!(Maybe::<i32>::Void.xor(Maybe::Void), Maybe::Void);
+        assert_eq!(Maybe::<i32>::Void.xor(Maybe::None), Maybe::None);
+    }
+
+    #[test]
+    pub fn it_flattens_nested_values() {
+        assert_eq!(Maybe::Some(Maybe::Some(1)).flatten(), Maybe::Some(1));
+        assert_eq!(Maybe::Some(Maybe::<i32>::Void).flatten(), Maybe::Void);
+        assert_eq!(Maybe::<Maybe<i32>>::None.flatten(), Maybe::None);
+    }
+
+    #[test]
+    pub fn it_round_trips_through_double_options() {
+        for maybe in [Maybe::Void, Maybe::None, Maybe::Some(1)] {
+            assert_eq!(Maybe::from_double_option(maybe.into_double_option()), maybe);
+        }
+        assert_eq!(Maybe::<i32>::Void.into_double_option(), None);
+        assert_eq!(Maybe::<i32>::None.into_double_option(), Some(None));
+        assert_eq!(Maybe::Some(1).into_double_option(), Some(Some(1)));
+    }
+
+    #[test]
+    pub fn it_flattens_and_transposes_inner_options() {
+        assert_eq!(Maybe::Some(Some(1)).flatten_option(), Maybe::Some(1));
+        assert_eq!(
+            Maybe::<Option<i32>>::Some(None).flatten_option(),
+            Maybe::None
+        );
+        assert_eq!(Maybe::<Option<i32>>::Void.flatten_option(), Maybe::Void);
+        assert_eq!(Maybe::Some(Some(1)).transpose(), Some(Maybe::Some(1)));
+        assert_eq!(Maybe::<Option<i32>>::Some(None).transpose(), None);
+        assert_eq!(Maybe::<Option<i32>>::Void.transpose(), Some(Maybe::Void));
+    }
+
+    #[test]
+    pub fn it_composes_sequential_edits() {
+        assert_eq!(Maybe::compose(Maybe::Some(1), Maybe::Void), Maybe::Some(1));
+        assert_eq!(Maybe::compose(Maybe::Some(1), Maybe::None), Maybe::None);
+        assert_eq!(Maybe::compose(Maybe::None, Maybe::Some(2)), Maybe::Some(2));
+    }
+
+    #[test]
+    pub fn it_inverts_edits() {
+        assert_eq!(Maybe::Some(2).invert(Some(&1)), Maybe::Some(1));
+        assert_eq!(Maybe::Some(2).invert(None), Maybe::None);
+        assert_eq!(Maybe::None.invert(Some(&1)), Maybe::Some(1));
+        assert_eq!(Maybe::<i32>::Void.invert(Some(&1)), Maybe::Void);
+    }
+
+    #[test]
+    pub fn it_takes_and_leaves_void() {
+        let mut maybe = Maybe::Some(5);
+        assert_eq!(maybe.take(), Maybe::Some(5));
+        assert_eq!(maybe, Maybe::Void);
+        assert_eq!(*maybe.get_or_insert(3), 3);
+        assert_eq!(maybe.replace(4), Maybe::Some(3));
+
+        assert_eq!(maybe.take_if(|value| *value > 4), Maybe::Void);
+        assert_eq!(maybe, Maybe::Some(4));
+        let mut maybe = Maybe::None;
+        assert_eq!(maybe.take_if(|_: &mut i32| true), Maybe::Void);
+        assert_eq!(maybe, Maybe::None);
+        assert_eq!(*maybe.get_or_insert_with(|| 6), 6);
+    }
+}
+
 #[cfg(all(test, feature = "async_graphql", feature = "serde"))]
 mod test {
     use super::*;
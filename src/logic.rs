@@ -0,0 +1,148 @@
+//! Kleene three-valued logic for `Maybe<bool>`.
+//!
+//! `None` is the unknown value, as `NULL` is in SQL: `false & None` is `false` and `true | None`
+//! is `true`, as the result doesn't depend on the unknown operand, while every other operation
+//! involving `None` is `None`.
+//!
+//! `Void` is an absent operand rather than an unknown one, so it is the identity of every binary
+//! operation: `Void & x`, `Void | x` and `Void ^ x` are all `x`, and `!Void` is `Void`. An
+//! absent filter therefore leaves the other filters alone instead of making them unknown.
+
+use crate::Maybe;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+/// Kleene conjunction, where `None` is unknown and `Void` is the identity.
+impl BitAnd for Maybe<bool> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Self::Void, other) | (other, Self::Void) => other,
+            (Self::Some(false), _) | (_, Self::Some(false)) => Self::Some(false),
+            (Self::Some(true), Self::Some(true)) => Self::Some(true),
+            _ => Self::None,
+        }
+    }
+}
+
+/// Kleene disjunction, where `None` is unknown and `Void` is the identity.
+impl BitOr for Maybe<bool> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Self::Void, other) | (other, Self::Void) => other,
+            (Self::Some(true), _) | (_, Self::Some(true)) => Self::Some(true),
+            (Self::Some(false), Self::Some(false)) => Self::Some(false),
+            _ => Self::None,
+        }
+    }
+}
+
+/// Kleene exclusive disjunction, where `None` is unknown and `Void` is the identity.
+impl BitXor for Maybe<bool> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Self::Void, other) | (other, Self::Void) => other,
+            (Self::Some(lhs), Self::Some(rhs)) => Self::Some(lhs ^ rhs),
+            _ => Self::None,
+        }
+    }
+}
+
+/// Negates `Some`, leaving `None` and `Void` as they are.
+impl Not for Maybe<bool> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.map(|value| !value)
+    }
+}
+
+impl Maybe<bool> {
+    /// Folds `values` with `&`, stopping at the first `false`. Returns `Void` if every value is
+    /// `Void`, including when there are none.
+    pub fn all<I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        let mut result = Self::Void;
+        for value in values {
+            result = result & value;
+            if result == Self::Some(false) {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Folds `values` with `|`, stopping at the first `true`. Returns `Void` if every value is
+    /// `Void`, including when there are none.
+    pub fn any<I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        let mut result = Self::Void;
+        for value in values {
+            result = result | value;
+            if result == Self::Some(true) {
+                break;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const T: Maybe<bool> = Maybe::Some(true);
+    const F: Maybe<bool> = Maybe::Some(false);
+    const U: Maybe<bool> = Maybe::None;
+    const V: Maybe<bool> = Maybe::Void;
+
+    #[test]
+    pub fn it_follows_kleene_truth_tables() {
+        assert_eq!([T & T, T & F, T & U, F & U, U & U], [T, F, U, F, U]);
+        assert_eq!([T | T, F | F, T | U, F | U, U | U], [T, F, T, U, U]);
+        assert_eq!([T ^ T, T ^ F, T ^ U, F ^ U, U ^ U], [F, T, U, U, U]);
+        assert_eq!([!T, !F, !U], [F, T, U]);
+    }
+
+    #[test]
+    pub fn it_treats_void_as_identity() {
+        for value in [T, F, U, V] {
+            assert_eq!(V & value, value);
+            assert_eq!(value & V, value);
+            assert_eq!(V | value, value);
+            assert_eq!(value | V, value);
+            assert_eq!(V ^ value, value);
+        }
+        assert_eq!(!V, V);
+    }
+
+    #[test]
+    pub fn it_folds_all_and_any() {
+        assert_eq!(Maybe::all([T, V, T]), T);
+        assert_eq!(Maybe::all([T, U, T]), U);
+        assert_eq!(Maybe::all([U, F, T]), F);
+        assert_eq!(Maybe::all([V, V]), V);
+        assert_eq!(Maybe::all([]), V);
+
+        assert_eq!(Maybe::any([F, V, F]), F);
+        assert_eq!(Maybe::any([F, U, F]), U);
+        assert_eq!(Maybe::any([U, T, F]), T);
+        assert_eq!(Maybe::any([]), V);
+    }
+
+    #[test]
+    pub fn it_stops_folding_once_the_result_is_known() {
+        let mut seen = 0;
+        let values = [F, T, T].into_iter().inspect(|_| seen += 1);
+        assert_eq!(Maybe::all(values), F);
+        assert_eq!(seen, 1);
+    }
+}
@@ -0,0 +1,364 @@
+//! Three-way merging of concurrent `Maybe` edits.
+//!
+//! Two patches made against a common base are merged field by field. A field edited on only one
+//! side (the other being `Void`) takes that edit, and edits which leave the base value unchanged
+//! count as no edit at all. A field set to different values on both sides is a [`Conflict`],
+//! which a [`Strategy`] may resolve.
+
+use crate::{Maybe, Patch};
+use std::{
+    error::Error,
+    fmt::{self, Debug},
+};
+
+/// A field set to different values by both sides of a merge.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Conflict<T> {
+    pub ours: Maybe<T>,
+    pub theirs: Maybe<T>,
+}
+
+/// Merges two edits of a value whose base is `base` (`None` when null or absent).
+pub fn merge3<T>(
+    base: Option<&T>,
+    ours: Maybe<T>,
+    theirs: Maybe<T>,
+) -> Result<Maybe<T>, Conflict<T>>
+where
+    T: PartialEq,
+{
+    let unchanged = |edit: &Maybe<T>| match edit {
+        Maybe::Void => true,
+        Maybe::None => base.is_none(),
+        Maybe::Some(value) => base == Some(value),
+    };
+    match (unchanged(&ours), unchanged(&theirs)) {
+        (true, true) => Ok(ours.or_void(theirs)),
+        (true, false) => Ok(theirs),
+        (false, true) => Ok(ours),
+        (false, false) if ours == theirs => Ok(ours),
+        (false, false) => Err(Conflict { ours, theirs }),
+    }
+}
+
+/// Decides the outcome of conflicting edits.
+///
+/// Strategies see the conflicting values of every field, so they are generic over the field type
+/// and are passed to [`Merge3::merge3`] as `&mut S` rather than as trait objects.
+pub trait Strategy {
+    /// Returns the merged value, or `None` to leave the conflict unresolved.
+    fn resolve<T>(&mut self, field: &'static str, conflict: Conflict<T>) -> Option<Maybe<T>>
+    where
+        T: PartialEq + Clone + Debug;
+}
+
+/// Resolves conflicts in favour of our side.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Ours;
+
+/// Resolves conflicts in favour of their side.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Theirs;
+
+/// Leaves conflicts unresolved, so merges with conflicts fail.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Fail;
+
+impl Strategy for Ours {
+    fn resolve<T>(&mut self, _: &'static str, conflict: Conflict<T>) -> Option<Maybe<T>>
+    where
+        T: PartialEq + Clone + Debug,
+    {
+        Some(conflict.ours)
+    }
+}
+
+impl Strategy for Theirs {
+    fn resolve<T>(&mut self, _: &'static str, conflict: Conflict<T>) -> Option<Maybe<T>>
+    where
+        T: PartialEq + Clone + Debug,
+    {
+        Some(conflict.theirs)
+    }
+}
+
+impl Strategy for Fail {
+    fn resolve<T>(&mut self, _: &'static str, _: Conflict<T>) -> Option<Maybe<T>>
+    where
+        T: PartialEq + Clone + Debug,
+    {
+        None
+    }
+}
+
+/// Returned when a merge leaves conflicts unresolved.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MergeError {
+    fields: Vec<&'static str>,
+}
+
+impl MergeError {
+    pub fn new(fields: Vec<&'static str>) -> Self {
+        Self { fields }
+    }
+
+    /// The fields with unresolved conflicts.
+    pub fn fields(&self) -> &[&'static str] {
+        &self.fields
+    }
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "conflicting edits to {}", self.fields.join(", "))
+    }
+}
+
+impl Error for MergeError {}
+
+/// A patch whose concurrent edits can be merged.
+///
+/// Implemented by `#[derive(Patch)]` with `#[patch(merge)]`.
+pub trait Merge3: Patch + Sized {
+    fn merge3<S>(
+        base: &Self::Target,
+        ours: Self,
+        theirs: Self,
+        strategy: &mut S,
+    ) -> Result<Self, MergeError>
+    where
+        S: Strategy;
+}
+
+/// Merges a single field, resolving conflicts with `strategy` and recording unresolved ones in
+/// `conflicts`. Used by derived [`Merge3`] impls.
+pub fn merge_field<T, S>(
+    field: &'static str,
+    base: Option<&T>,
+    ours: Maybe<T>,
+    theirs: Maybe<T>,
+    strategy: &mut S,
+    conflicts: &mut Vec<&'static str>,
+) -> Maybe<T>
+where
+    T: PartialEq + Clone + Debug,
+    S: Strategy,
+{
+    match merge3(base, ours, theirs) {
+        Ok(merged) => merged,
+        Err(conflict) => strategy.resolve(field, conflict).unwrap_or_else(|| {
+            conflicts.push(field);
+            Maybe::Void
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn it_takes_one_sided_edits() {
+        assert_eq!(
+            merge3(Some(&1), Maybe::Some(2), Maybe::Void),
+            Ok(Maybe::Some(2))
+        );
+        assert_eq!(merge3(Some(&1), Maybe::Void, Maybe::None), Ok(Maybe::None));
+        assert_eq!(
+            merge3::<i32>(Some(&1), Maybe::Void, Maybe::Void),
+            Ok(Maybe::Void)
+        );
+    }
+
+    #[test]
+    pub fn it_ignores_edits_which_keep_the_base_value() {
+        assert_eq!(
+            merge3(Some(&1), Maybe::Some(1), Maybe::Some(2)),
+            Ok(Maybe::Some(2))
+        );
+        assert_eq!(
+            merge3(None, Maybe::None, Maybe::Some(2)),
+            Ok(Maybe::Some(2))
+        );
+    }
+
+    #[test]
+    pub fn it_accepts_identical_edits() {
+        assert_eq!(merge3(Some(&1), Maybe::None, Maybe::None), Ok(Maybe::None));
+        assert_eq!(
+            merge3(None, Maybe::Some(3), Maybe::Some(3)),
+            Ok(Maybe::Some(3))
+        );
+    }
+
+    #[test]
+    pub fn it_reports_conflicting_edits() {
+        assert_eq!(
+            merge3(Some(&1), Maybe::Some(2), Maybe::None),
+            Err(Conflict {
+                ours: Maybe::Some(2),
+                theirs: Maybe::None,
+            })
+        );
+    }
+
+    #[test]
+    pub fn it_resolves_conflicts_with_strategies() {
+        let mut conflicts = Vec::new();
+        let merged = merge_field(
+            "a",
+            Some(&1),
+            Maybe::Some(2),
+            Maybe::Some(3),
+            &mut Ours,
+            &mut conflicts,
+        );
+        assert_eq!(merged, Maybe::Some(2));
+        let merged = merge_field(
+            "a",
+            Some(&1),
+            Maybe::Some(2),
+            Maybe::Some(3),
+            &mut Theirs,
+            &mut conflicts,
+        );
+        assert_eq!(merged, Maybe::Some(3));
+        assert!(conflicts.is_empty());
+        merge_field(
+            "a",
+            Some(&1),
+            Maybe::Some(2),
+            Maybe::Some(3),
+            &mut Fail,
+            &mut conflicts,
+        );
+        assert_eq!(conflicts, vec!["a"]);
+    }
+
+    /// Keeps whichever side sets a value over one clearing it, logging what it resolved.
+    #[derive(Default)]
+    struct PreferValues {
+        log: Vec<String>,
+    }
+
+    impl Strategy for PreferValues {
+        fn resolve<T>(&mut self, field: &'static str, conflict: Conflict<T>) -> Option<Maybe<T>>
+        where
+            T: PartialEq + Clone + Debug,
+        {
+            self.log.push(format!(
+                "{}: {:?} / {:?}",
+                field, conflict.ours, conflict.theirs
+            ));
+            match (conflict.ours, conflict.theirs) {
+                (Maybe::None, theirs) => Some(theirs),
+                (ours, Maybe::None) => Some(ours),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    pub fn it_resolves_conflicts_with_custom_strategies() {
+        let mut strategy = PreferValues::default();
+        let mut conflicts = Vec::new();
+        let merged = merge_field(
+            "a",
+            Some(&1),
+            Maybe::None,
+            Maybe::Some(3),
+            &mut strategy,
+            &mut conflicts,
+        );
+        assert_eq!(merged, Maybe::Some(3));
+        let merged = merge_field(
+            "b",
+            Some(&"x"),
+            Maybe::Some("y"),
+            Maybe::Some("z"),
+            &mut strategy,
+            &mut conflicts,
+        );
+        assert_eq!(merged, Maybe::Void);
+        assert_eq!(conflicts, vec!["b"]);
+        assert_eq!(
+            strategy.log,
+            vec![
+                "a: None / Some(3)".to_string(),
+                r#"b: Some("y") / Some("z")"#.to_string(),
+            ]
+        );
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod derive_test {
+    use super::*;
+
+    #[derive(Patch, Debug, PartialEq)]
+    #[patch(merge, derive(Debug, PartialEq))]
+    struct Document {
+        title: String,
+        body: String,
+        summary: Option<String>,
+    }
+
+    fn base() -> Document {
+        Document {
+            title: "Title".into(),
+            body: "Body".into(),
+            summary: Some("Summary".into()),
+        }
+    }
+
+    #[test]
+    pub fn it_merges_disjoint_patches() {
+        let ours = DocumentPatch {
+            title: Maybe::Some("New title".into()),
+            ..Default::default()
+        };
+        let theirs = DocumentPatch {
+            summary: Maybe::None,
+            ..Default::default()
+        };
+        let merged = DocumentPatch::merge3(&base(), ours, theirs, &mut Fail).unwrap();
+        assert_eq!(
+            merged,
+            DocumentPatch {
+                title: Maybe::Some("New title".into()),
+                body: Maybe::Void,
+                summary: Maybe::None,
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_reports_conflicting_fields() {
+        let ours = DocumentPatch {
+            body: Maybe::Some("Ours".into()),
+            summary: Maybe::Some("Ours".into()),
+            ..Default::default()
+        };
+        let theirs = DocumentPatch {
+            body: Maybe::Some("Theirs".into()),
+            summary: Maybe::None,
+            ..Default::default()
+        };
+        let error = DocumentPatch::merge3(&base(), ours, theirs, &mut Fail).unwrap_err();
+        assert_eq!(error.fields(), ["body", "summary"]);
+    }
+
+    #[test]
+    pub fn it_resolves_conflicting_fields_with_the_strategy() {
+        let ours = DocumentPatch {
+            body: Maybe::Some("Ours".into()),
+            ..Default::default()
+        };
+        let theirs = DocumentPatch {
+            body: Maybe::Some("Theirs".into()),
+            ..Default::default()
+        };
+        let merged = DocumentPatch::merge3(&base(), ours, theirs, &mut Theirs).unwrap();
+        assert_eq!(merged.body, Maybe::Some("Theirs".into()));
+    }
+}
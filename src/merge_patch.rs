@@ -0,0 +1,256 @@
+//! JSON Merge Patch ([RFC 7396](https://www.rfc-editor.org/rfc/rfc7396)) documents.
+//!
+//! A merge patch maps directly onto `Maybe`: a missing member leaves the target alone (`Void`),
+//! `null` removes it (`None`) and any other value replaces it (`Some`). A struct of `Maybe`
+//! fields annotated with `#[serde(default, skip_serializing_if = "Maybe::is_void")]` therefore
+//! serializes to, and deserializes from, a merge patch document.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+/// Applies `patch` to `target` as described by the RFC.
+pub fn apply(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        unreachable!()
+    };
+    for (name, value) in patch {
+        if value.is_null() {
+            target.remove(name);
+        } else {
+            apply(target.entry(name.as_str()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Computes a patch which turns `source` into `target` when applied.
+///
+/// Merge patches can't set members to `null`, so `null` members of `target` are treated as
+/// absent.
+pub fn diff(source: &Value, target: &Value) -> Value {
+    let (Value::Object(source), Value::Object(target)) = (source, target) else {
+        return target.clone();
+    };
+    let mut patch = Map::new();
+    for name in source.keys() {
+        if target.get(name).is_none_or(Value::is_null) {
+            patch.insert(name.clone(), Value::Null);
+        }
+    }
+    for (name, value) in target.iter().filter(|(_, value)| !value.is_null()) {
+        match source.get(name) {
+            Some(existing) if existing == value => {}
+            Some(existing) => {
+                patch.insert(name.clone(), diff(existing, value));
+            }
+            None => {
+                patch.insert(name.clone(), strip_nulls(value));
+            }
+        }
+    }
+    Value::Object(patch)
+}
+
+fn strip_nulls(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(name, value)| (name.clone(), strip_nulls(value)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Computes a patch between the serialized forms of `source` and `target`.
+pub fn diff_serialize<T>(source: &T, target: &T) -> serde_json::Result<Value>
+where
+    T: Serialize,
+{
+    Ok(diff(
+        &serde_json::to_value(source)?,
+        &serde_json::to_value(target)?,
+    ))
+}
+
+/// Applies `patch` to the serialized form of `target`, deserializing the result.
+pub fn apply_serialize<T>(target: &T, patch: &Value) -> serde_json::Result<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut value = serde_json::to_value(target)?;
+    apply(&mut value, patch);
+    serde_json::from_value(value)
+}
+
+/// Converts a merge patch document into a typed struct of `Maybe` fields.
+pub fn from_document<P>(document: Value) -> serde_json::Result<P>
+where
+    P: DeserializeOwned,
+{
+    serde_json::from_value(document)
+}
+
+/// Converts a typed struct of `Maybe` fields into a merge patch document.
+pub fn to_document<P>(patch: &P) -> serde_json::Result<Value>
+where
+    P: Serialize,
+{
+    serde_json::to_value(patch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Maybe;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    /// The examples from Appendix A of the RFC, as (original, patch, result).
+    fn rfc_examples() -> Vec<(Value, Value, Value)> {
+        vec![
+            (json!({"a":"b"}), json!({"a":"c"}), json!({"a":"c"})),
+            (json!({"a":"b"}), json!({"b":"c"}), json!({"a":"b","b":"c"})),
+            (json!({"a":"b"}), json!({"a":null}), json!({})),
+            (
+                json!({"a":"b","b":"c"}),
+                json!({"a":null}),
+                json!({"b":"c"}),
+            ),
+            (json!({"a":["b"]}), json!({"a":"c"}), json!({"a":"c"})),
+            (json!({"a":"c"}), json!({"a":["b"]}), json!({"a":["b"]})),
+            (
+                json!({"a":{"b":"c"}}),
+                json!({"a":{"b":"d","c":null}}),
+                json!({"a":{"b":"d"}}),
+            ),
+            (json!({"a":[{"b":"c"}]}), json!({"a":[1]}), json!({"a":[1]})),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a":"b"}), json!(["c"]), json!(["c"])),
+            (json!({"a":"foo"}), json!(null), json!(null)),
+            (json!({"a":"foo"}), json!("bar"), json!("bar")),
+            (json!({"e":null}), json!({"a":1}), json!({"e":null,"a":1})),
+            (json!([1, 2]), json!({"a":"b","c":null}), json!({"a":"b"})),
+            (
+                json!({}),
+                json!({"a":{"bb":{"ccc":null}}}),
+                json!({"a":{"bb":{}}}),
+            ),
+        ]
+    }
+
+    #[test]
+    pub fn it_applies_the_rfc_examples() {
+        for (original, patch, expected) in rfc_examples() {
+            let mut target = original.clone();
+            apply(&mut target, &patch);
+            assert_eq!(target, expected, "{} + {}", original, patch);
+        }
+    }
+
+    #[test]
+    pub fn it_diffs_into_patches_reproducing_the_rfc_examples() {
+        for (original, _, expected) in rfc_examples() {
+            let patch = diff(&original, &expected);
+            let mut target = original.clone();
+            apply(&mut target, &patch);
+            assert_eq!(
+                strip_nulls(&target),
+                strip_nulls(&expected),
+                "{} -> {}",
+                original,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    pub fn it_computes_minimal_diffs() {
+        let source = json!({"a": 1, "b": {"c": 2, "d": 3}, "e": 4});
+        let target = json!({"a": 1, "b": {"c": 5, "d": 3}, "f": 6});
+        assert_eq!(
+            diff(&source, &target),
+            json!({"b": {"c": 5}, "e": null, "f": 6})
+        );
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+    struct User {
+        name: String,
+        email: Option<String>,
+        address: Address,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+    struct Address {
+        city: String,
+        zip: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+    struct UserPatch {
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        name: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        email: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        address: Maybe<AddressPatch>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+    struct AddressPatch {
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        city: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_void")]
+        zip: Maybe<String>,
+    }
+
+    fn user() -> User {
+        User {
+            name: "Ferris".into(),
+            email: Some("ferris@example.com".into()),
+            address: Address {
+                city: "Crabtown".into(),
+                zip: "1234".into(),
+            },
+        }
+    }
+
+    #[test]
+    pub fn it_diffs_and_applies_structs() {
+        let mut changed = user();
+        changed.email = None;
+        changed.address.city = "Rustville".into();
+        let patch = diff_serialize(&user(), &changed).unwrap();
+        assert_eq!(
+            patch,
+            json!({"email": null, "address": {"city": "Rustville"}})
+        );
+        assert_eq!(apply_serialize(&user(), &patch).unwrap(), changed);
+    }
+
+    #[test]
+    pub fn it_converts_between_documents_and_typed_patches() {
+        let document = json!({"email": null, "address": {"zip": "9999"}});
+        let patch: UserPatch = from_document(document.clone()).unwrap();
+        assert_eq!(
+            patch,
+            UserPatch {
+                name: Maybe::Void,
+                email: Maybe::None,
+                address: Maybe::Some(AddressPatch {
+                    city: Maybe::Void,
+                    zip: Maybe::Some("9999".into()),
+                }),
+            }
+        );
+        assert_eq!(to_document(&patch).unwrap(), document);
+    }
+}
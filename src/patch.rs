@@ -0,0 +1,194 @@
+use std::{error::Error, fmt};
+
+/// A set of changes that can be applied to a value of type `Target`.
+///
+/// Usually implemented by `#[derive(Patch)]` (with the `derive` feature), which generates a
+/// struct of `Maybe` fields for the target. `Void` fields leave the target untouched, `Some`
+/// fields overwrite it and `None` fields clear `Option` fields.
+pub trait Patch {
+    type Target;
+
+    fn apply(self, target: &mut Self::Target) -> Result<(), PatchError>;
+}
+
+/// A patch which can be squashed with a later one, field by field with
+/// [`Maybe::compose`](crate::Maybe::compose).
+///
+/// Implemented by `#[derive(Patch)]`.
+pub trait Compose {
+    fn compose(self, later: Self) -> Self;
+}
+
+/// A patch which can produce the patch undoing it.
+///
+/// Implemented by `#[derive(Patch)]` with `#[patch(invert)]`.
+pub trait Invert: Patch {
+    /// Returns the patch restoring `original` after `self` has been applied to it.
+    fn invert(&self, original: &Self::Target) -> Self;
+}
+
+/// Returned when a patch sets a non-optional field to `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+    field: &'static str,
+}
+
+impl PatchError {
+    pub fn null_field(field: &'static str) -> Self {
+        Self { field }
+    }
+
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "field '{}' is not optional and cannot be set to null",
+            self.field
+        )
+    }
+}
+
+impl Error for PatchError {}
+
+#[cfg(all(test, feature = "derive"))]
+mod test {
+    use crate::{Compose, Invert, Maybe, Patch, PatchError};
+
+    #[derive(Patch, Debug, Clone, PartialEq)]
+    #[patch(invert, derive(Debug, PartialEq))]
+    #[cfg_attr(feature = "serde", patch(serde))]
+    struct User {
+        #[patch(skip)]
+        id: u32,
+        name: String,
+        email: Option<String>,
+        age: Option<u8>,
+    }
+
+    /// Patches only derive serde traits when asked to, so fields needn't be serializable.
+    #[derive(Patch)]
+    #[allow(dead_code)]
+    struct Settings {
+        theme: Theme,
+    }
+
+    struct Theme;
+
+    fn user() -> User {
+        User {
+            id: 1,
+            name: "Ferris".into(),
+            email: Some("ferris@example.com".into()),
+            age: Some(9),
+        }
+    }
+
+    #[test]
+    pub fn it_leaves_void_fields_untouched() {
+        let mut target = user();
+        UserPatch::default().apply(&mut target).unwrap();
+        assert_eq!(target, user());
+    }
+
+    #[test]
+    pub fn it_sets_some_and_clears_none_fields() {
+        let mut target = user();
+        UserPatch {
+            name: Maybe::Some("Corro".into()),
+            email: Maybe::None,
+            age: Maybe::Void,
+        }
+        .apply(&mut target)
+        .unwrap();
+        assert_eq!(
+            target,
+            User {
+                id: 1,
+                name: "Corro".into(),
+                email: None,
+                age: Some(9),
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_rejects_none_on_required_fields_without_applying() {
+        let mut target = user();
+        let result = UserPatch {
+            name: Maybe::None,
+            email: Maybe::None,
+            age: Maybe::Void,
+        }
+        .apply(&mut target);
+        assert_eq!(result, Err(PatchError::null_field("name")));
+        assert_eq!(target, user());
+    }
+
+    #[test]
+    pub fn it_composes_sequential_patches() {
+        let earlier = UserPatch {
+            name: Maybe::Some("Corro".into()),
+            email: Maybe::None,
+            age: Maybe::Void,
+        };
+        let later = UserPatch {
+            name: Maybe::Void,
+            email: Maybe::Some("corro@example.com".into()),
+            age: Maybe::Some(3),
+        };
+        assert_eq!(
+            earlier.compose(later),
+            UserPatch {
+                name: Maybe::Some("Corro".into()),
+                email: Maybe::Some("corro@example.com".into()),
+                age: Maybe::Some(3),
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_inverts_patches() {
+        let patch = UserPatch {
+            name: Maybe::Some("Corro".into()),
+            email: Maybe::None,
+            age: Maybe::Void,
+        };
+        let inverse = patch.invert(&user());
+        assert_eq!(
+            inverse,
+            UserPatch {
+                name: Maybe::Some("Ferris".into()),
+                email: Maybe::Some("ferris@example.com".into()),
+                age: Maybe::Void,
+            }
+        );
+
+        let mut target = user();
+        patch.apply(&mut target).unwrap();
+        inverse.apply(&mut target).unwrap();
+        assert_eq!(target, user());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn it_generates_serde_annotations() {
+        let patch: UserPatch = serde_json::from_str(r#"{"email":null,"age":3}"#).unwrap();
+        assert_eq!(
+            patch,
+            UserPatch {
+                name: Maybe::Void,
+                email: Maybe::None,
+                age: Maybe::Some(3),
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&patch).unwrap(),
+            r#"{"email":null,"age":3}"#
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Protobuf field presence support through `prost`.
+//!
+//! Proto3 `optional` fields and `google.protobuf.*Value` wrappers are generated by prost as
+//! `Option<T>`, and partial updates carry a `google.protobuf.FieldMask`. An [`UpdateMask`] reads
+//! the fields of such a request as `Maybe` values, with masked out fields becoming `Void` and
+//! masked in fields without a value becoming `None`. Requests without a mask are read as chosen
+//! by a [`MissingMask`]. [`FieldMask::record`] goes the other way, building the mask while
+//! filling in a message.
+
+use crate::{field_mask::FieldMask, Maybe};
+
+impl From<prost_types::FieldMask> for FieldMask {
+    fn from(mask: prost_types::FieldMask) -> Self {
+        Self { paths: mask.paths }
+    }
+}
+
+impl From<FieldMask> for prost_types::FieldMask {
+    fn from(mask: FieldMask) -> Self {
+        Self { paths: mask.paths }
+    }
+}
+
+/// What an update request without a field mask updates.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MissingMask {
+    /// Every populated field, as [AIP-134](https://google.aip.dev/134) specifies, so fields
+    /// without a value are `Void`.
+    Populated,
+    /// Nothing, so every field is `Void`.
+    Nothing,
+}
+
+/// The field mask of an update request, for reading its fields as `Maybe` values.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UpdateMask {
+    /// `None` when every populated field is updated.
+    mask: Option<FieldMask>,
+}
+
+impl UpdateMask {
+    pub fn new(mask: Option<prost_types::FieldMask>, missing: MissingMask) -> Self {
+        let mask = match (mask, missing) {
+            (Some(mask), _) => Some(mask.into()),
+            (None, MissingMask::Populated) => None,
+            (None, MissingMask::Nothing) => Some(FieldMask::default()),
+        };
+        Self { mask }
+    }
+
+    /// Reads a field with presence: a proto3 `optional` field, a wrapper type or a message.
+    pub fn field<T>(&self, path: &str, value: Option<T>) -> Maybe<T> {
+        match &self.mask {
+            Some(mask) => mask.masked(path, value),
+            None => value.map_or(Maybe::Void, Maybe::Some),
+        }
+    }
+
+    /// Reads a field without presence. Without a mask, its default value counts as unpopulated
+    /// and is `Void`.
+    pub fn scalar<T>(&self, path: &str, value: T) -> Maybe<T>
+    where
+        T: Default + PartialEq,
+    {
+        match &self.mask {
+            Some(mask) => mask.masked(path, Some(value)),
+            None if value == T::default() => Maybe::Void,
+            None => Maybe::Some(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use prost::Message;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct User {
+        #[prost(string, tag = "1")]
+        name: String,
+        #[prost(string, optional, tag = "2")]
+        email: Option<String>,
+        #[prost(message, optional, tag = "3")]
+        nickname: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct UpdateUserRequest {
+        #[prost(message, optional, tag = "1")]
+        user: Option<User>,
+        #[prost(message, optional, tag = "2")]
+        update_mask: Option<prost_types::FieldMask>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct UserPatch {
+        name: Maybe<String>,
+        email: Maybe<String>,
+        nickname: Maybe<String>,
+    }
+
+    impl UserPatch {
+        fn from_request(request: UpdateUserRequest, missing: MissingMask) -> Self {
+            let mask = UpdateMask::new(request.update_mask, missing);
+            let user = request.user.unwrap_or_default();
+            Self {
+                name: mask.scalar("name", user.name),
+                email: mask.field("email", user.email),
+                nickname: mask.field("nickname", user.nickname),
+            }
+        }
+    }
+
+    impl From<UserPatch> for UpdateUserRequest {
+        fn from(patch: UserPatch) -> Self {
+            let mut mask = FieldMask::default();
+            let user = User {
+                name: mask.record("name", patch.name).unwrap_or_default(),
+                email: mask.record("email", patch.email),
+                nickname: mask.record("nickname", patch.nickname),
+            };
+            Self {
+                user: Some(user),
+                update_mask: Some(mask.into()),
+            }
+        }
+    }
+
+    fn round_trip(request: UpdateUserRequest) -> UpdateUserRequest {
+        UpdateUserRequest::decode(request.encode_to_vec().as_slice()).unwrap()
+    }
+
+    #[test]
+    pub fn it_turns_masked_requests_into_patches() {
+        let request = round_trip(UpdateUserRequest {
+            user: Some(User {
+                name: "Ferris".into(),
+                email: None,
+                nickname: Some("Crab".into()),
+            }),
+            update_mask: Some(prost_types::FieldMask {
+                paths: vec!["email".into(), "nickname".into()],
+            }),
+        });
+        assert_eq!(
+            UserPatch::from_request(request, MissingMask::Nothing),
+            UserPatch {
+                name: Maybe::Void,
+                email: Maybe::None,
+                nickname: Maybe::Some("Crab".into()),
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_turns_patches_into_masked_requests() {
+        let patch = UserPatch {
+            name: Maybe::Some("Corro".into()),
+            email: Maybe::Void,
+            nickname: Maybe::None,
+        };
+        let request = round_trip(patch.into());
+        assert_eq!(
+            request.update_mask.as_ref().unwrap().paths,
+            vec!["name".to_string(), "nickname".to_string()]
+        );
+        assert_eq!(
+            UserPatch::from_request(request, MissingMask::Nothing),
+            UserPatch {
+                name: Maybe::Some("Corro".into()),
+                email: Maybe::Void,
+                nickname: Maybe::None,
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_reads_requests_without_a_mask_as_chosen() {
+        let request = UpdateUserRequest {
+            user: Some(User {
+                name: "Ferris".into(),
+                email: None,
+                nickname: Some(String::new()),
+            }),
+            update_mask: None,
+        };
+        assert_eq!(
+            UserPatch::from_request(request.clone(), MissingMask::Populated),
+            UserPatch {
+                name: Maybe::Some("Ferris".into()),
+                email: Maybe::Void,
+                nickname: Maybe::Some(String::new()),
+            }
+        );
+        assert_eq!(
+            UserPatch::from_request(request, MissingMask::Nothing),
+            UserPatch {
+                name: Maybe::Void,
+                email: Maybe::Void,
+                nickname: Maybe::Void,
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_leaves_default_scalars_out_without_a_mask() {
+        let mask = UpdateMask::new(None, MissingMask::Populated);
+        assert_eq!(mask.scalar("name", String::new()), Maybe::Void);
+        assert_eq!(mask.scalar("age", 0), Maybe::Void);
+        assert_eq!(mask.scalar("age", 9), Maybe::Some(9));
+        let mask = UpdateMask::new(
+            Some(prost_types::FieldMask {
+                paths: vec!["name".into()],
+            }),
+            MissingMask::Populated,
+        );
+        assert_eq!(
+            mask.scalar("name", String::new()),
+            Maybe::Some(String::new())
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Variants of `Maybe` which only allow two of its three states.
+
+use crate::Maybe;
+use std::{error::Error, fmt};
+
+/// A value which may be omitted but must not be null.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Omittable<T> {
+    #[default]
+    Void,
+    Some(T),
+}
+
+/// A value which must be provided but may be null.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Nullable<T> {
+    #[default]
+    None,
+    Some(T),
+}
+
+/// Returned when a `Maybe` is in a state that the target type doesn't allow.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RestrictionError {
+    UnexpectedNull,
+    UnexpectedVoid,
+}
+
+impl fmt::Display for RestrictionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedNull => write!(f, "value may be omitted but must not be null"),
+            Self::UnexpectedVoid => write!(f, "value may be null but must not be omitted"),
+        }
+    }
+}
+
+impl Error for RestrictionError {}
+
+impl<T> Omittable<T> {
+    pub fn is_void(&self) -> bool {
+        matches!(self, Self::Void)
+    }
+
+    pub fn is_some(&self) -> bool {
+        matches!(self, Self::Some(_))
+    }
+
+    pub fn as_maybe(&self) -> Maybe<&T> {
+        match self {
+            Self::Void => Maybe::Void,
+            Self::Some(value) => Maybe::Some(value),
+        }
+    }
+
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Self::Void => None,
+            Self::Some(value) => Some(value),
+        }
+    }
+}
+
+impl<T> Nullable<T> {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn is_some(&self) -> bool {
+        matches!(self, Self::Some(_))
+    }
+
+    pub fn as_maybe(&self) -> Maybe<&T> {
+        match self {
+            Self::None => Maybe::None,
+            Self::Some(value) => Maybe::Some(value),
+        }
+    }
+
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Self::None => None,
+            Self::Some(value) => Some(value),
+        }
+    }
+}
+
+impl<T> From<Omittable<T>> for Maybe<T> {
+    fn from(value: Omittable<T>) -> Self {
+        match value {
+            Omittable::Void => Maybe::Void,
+            Omittable::Some(value) => Maybe::Some(value),
+        }
+    }
+}
+
+impl<T> From<Nullable<T>> for Maybe<T> {
+    fn from(value: Nullable<T>) -> Self {
+        match value {
+            Nullable::None => Maybe::None,
+            Nullable::Some(value) => Maybe::Some(value),
+        }
+    }
+}
+
+impl<T> TryFrom<Maybe<T>> for Omittable<T> {
+    type Error = RestrictionError;
+
+    fn try_from(value: Maybe<T>) -> Result<Self, Self::Error> {
+        match value {
+            Maybe::Void => Ok(Self::Void),
+            Maybe::None => Err(RestrictionError::UnexpectedNull),
+            Maybe::Some(value) => Ok(Self::Some(value)),
+        }
+    }
+}
+
+impl<T> TryFrom<Maybe<T>> for Nullable<T> {
+    type Error = RestrictionError;
+
+    fn try_from(value: Maybe<T>) -> Result<Self, Self::Error> {
+        match value {
+            Maybe::Void => Err(RestrictionError::UnexpectedVoid),
+            Maybe::None => Ok(Self::None),
+            Maybe::Some(value) => Ok(Self::Some(value)),
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Nullable<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            None => Self::None,
+            Some(value) => Self::Some(value),
+        }
+    }
+}
+
+impl<T> From<Nullable<T>> for Option<T> {
+    fn from(value: Nullable<T>) -> Self {
+        value.into_option()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn it_converts_infallibly_into_maybe() {
+        assert_eq!(Maybe::from(Omittable::<i32>::Void), Maybe::Void);
+        assert_eq!(Maybe::from(Omittable::Some(1)), Maybe::Some(1));
+        assert_eq!(Maybe::from(Nullable::<i32>::None), Maybe::None);
+        assert_eq!(Maybe::from(Nullable::Some(1)), Maybe::Some(1));
+    }
+
+    #[test]
+    pub fn it_rejects_forbidden_states_from_maybe() {
+        assert_eq!(
+            Omittable::try_from(Maybe::<i32>::None),
+            Err(RestrictionError::UnexpectedNull)
+        );
+        assert_eq!(
+            Nullable::try_from(Maybe::<i32>::Void),
+            Err(RestrictionError::UnexpectedVoid)
+        );
+        assert_eq!(Omittable::try_from(Maybe::Some(1)), Ok(Omittable::Some(1)));
+        assert_eq!(Nullable::try_from(Maybe::Some(1)), Ok(Nullable::Some(1)));
+    }
+}
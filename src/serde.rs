@@ -0,0 +1,542 @@
+//! Helpers for serializing `Maybe` values that aren't skipped by `skip_serializing_if`.
+//!
+//! A `Void` struct field is best skipped with
+//! `#[serde(default, skip_serializing_if = "Maybe::is_void")]`, which `#[maybe::serde_fields]`
+//! adds automatically. Everywhere else (sequences, top level values, fields that must always be
+//! present) a `Void` is an error by default, and the functions below pick another policy.
+//!
+//! [`deny_null`] and [`deny_void`] reject states that a field's schema doesn't allow while
+//! deserializing, and [`nested`] marks fields holding nested patches.
+
+use crate::{Maybe, Nullable, Omittable, RestrictionError};
+use ::serde::{
+    de::{Error as DeError, Visitor},
+    ser::{Error as SerError, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{fmt, marker::PhantomData};
+
+/// Serializes `Void` as `null`. Usable with `#[serde(serialize_with = "...")]`.
+pub fn void_as_null<T, S>(value: &Maybe<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    match value {
+        Maybe::Some(value) => serializer.serialize_some(value),
+        _ => serializer.serialize_none(),
+    }
+}
+
+/// Serializes a sequence of `Maybe` values, writing `Void` elements as `null`.
+pub fn seq_void_as_null<'a, I, T, S>(items: &'a I, serializer: S) -> Result<S::Ok, S::Error>
+where
+    I: ?Sized,
+    &'a I: IntoIterator<Item = &'a Maybe<T>>,
+    T: Serialize + 'a,
+    S: Serializer,
+{
+    serializer.collect_seq(
+        items
+            .into_iter()
+            .map(|item| item.as_ref().or_void(Maybe::None)),
+    )
+}
+
+/// Serializes a sequence of `Maybe` values, leaving out `Void` elements.
+pub fn seq_skip_void<'a, I, T, S>(items: &'a I, serializer: S) -> Result<S::Ok, S::Error>
+where
+    I: ?Sized,
+    &'a I: IntoIterator<Item = &'a Maybe<T>>,
+    T: Serialize + 'a,
+    S: Serializer,
+{
+    let mut seq = serializer.serialize_seq(None)?;
+    for item in items.into_iter().filter(|item| item.is_defined()) {
+        seq.serialize_element(item)?;
+    }
+    seq.end()
+}
+
+/// Rejects explicit nulls, for use with `#[serde(default, with = "maybe::serde::deny_null")]`.
+///
+/// The field should still be skipped when `Void`. serde doesn't tell `with` modules which field
+/// they're deserializing, so the error is only "null is not allowed", without the field name.
+/// `#[maybe(deny_null)]` under `#[maybe::serde_fields]` does all of this and names the field,
+/// through [`deserialize_field`].
+pub mod deny_null {
+    use crate::Maybe;
+    use ::serde::{de::Error as DeError, ser::Error as SerError};
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T, S>(value: &Maybe<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Maybe::None => Err(SerError::custom("null is not allowed")),
+            value => value.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Maybe<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        match Maybe::deserialize(deserializer)? {
+            Maybe::None => Err(DeError::custom("null is not allowed")),
+            value => Ok(value),
+        }
+    }
+
+    /// Like [`deserialize`], but names `field` in the error.
+    pub fn deserialize_field<'de, T, D>(
+        deserializer: D,
+        field: &'static str,
+    ) -> Result<Maybe<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        match Maybe::deserialize(deserializer)? {
+            Maybe::None => Err(DeError::custom(format_args!(
+                "null is not allowed for field `{}`",
+                field
+            ))),
+            value => Ok(value),
+        }
+    }
+}
+
+/// Rejects missing values, for use with `#[serde(with = "maybe::serde::deny_void")]`.
+///
+/// Fields deserialized with a `with` module are required by serde unless they're marked
+/// `default`, so a missing field fails with serde's own `missing field` error. Don't combine
+/// this with `#[serde(default)]`.
+pub mod deny_void {
+    use crate::Maybe;
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T, S>(value: &Maybe<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Maybe<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Maybe::deserialize(deserializer)
+    }
+}
+
+/// Marks a field holding a nested patch, for use with
+/// `#[serde(default, skip_serializing_if = "Maybe::is_void", with = "maybe::serde::nested")]`.
+///
+/// Marked fields serialize as usual, except that the generators walking a patch (JSON Patch
+/// operations, field masks and BSON update documents) descend into them and update their fields
+/// individually. Any other `Some` value is set whole, even if it serializes to an object.
+pub mod nested {
+    use crate::Maybe;
+    use ::serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+    use std::cell::Cell;
+
+    /// The key of the single-member map wrapping marked values while they're tagged.
+    pub(crate) const TAG: &str = "$maybe::nested";
+
+    thread_local! {
+        static TAGGING: Cell<bool> = const { Cell::new(false) };
+    }
+
+    pub fn serialize<T, S>(value: &Maybe<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Maybe::Some(value) if TAGGING.with(Cell::get) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(TAG, value)?;
+                map.end()
+            }
+            value => value.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Maybe<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Maybe::deserialize(deserializer)
+    }
+
+    /// Runs `f` with the `Some` values of marked fields wrapped in a map keyed by [`TAG`], so
+    /// that they can be told apart from other values once serialized.
+    #[cfg(any(feature = "json", feature = "bson"))]
+    pub(crate) fn tagged<R>(f: impl FnOnce() -> R) -> R {
+        struct Restore(bool);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                TAGGING.with(|tagging| tagging.set(self.0));
+            }
+        }
+
+        let _restore = Restore(TAGGING.with(|tagging| tagging.replace(true)));
+        f()
+    }
+
+    /// Returns the value wrapped by a serialized map if it is tagged, given its number of
+    /// members and its first member. Any value can be tagged, not only nested patches.
+    #[cfg(any(feature = "json", feature = "bson"))]
+    pub(crate) fn tagged_value<K: AsRef<str>, V>(len: usize, first: Option<(K, V)>) -> Option<V> {
+        match first {
+            Some((name, value)) if len == 1 && name.as_ref() == TAG => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Deserializes an `Option`, treating a missing field as an error rather than as `None`.
+///
+/// Serde reports missing fields by deserializing `None`, but only when asked for an option. By
+/// asking for a newtype instead, a missing field becomes a `missing field` error while self
+/// describing formats still pass `null` and values through.
+fn deserialize_present<'de, T, D>(
+    deserializer: D,
+    name: &'static str,
+) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    struct PresentVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for PresentVisitor<T>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = Option<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an optional value")
+        }
+
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Option::deserialize(deserializer)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            T::deserialize(deserializer).map(Some)
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+    }
+
+    deserializer.deserialize_newtype_struct(name, PresentVisitor(PhantomData))
+}
+
+impl<'de, T> Deserialize<'de> for Omittable<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match deserialize_present(deserializer, "Omittable")? {
+            Some(value) => Ok(Self::Some(value)),
+            None => Err(DeError::custom(RestrictionError::UnexpectedNull)),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Omittable<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let error_message = r#"'Omittable' fields need to be annotated with:
+        #[serde(default, skip_serializing_if = "Omittable::is_void")]
+        or the struct with #[maybe::serde_fields]
+        "#;
+        match self {
+            Omittable::Some(value) => value.serialize(serializer),
+            // should have been skipped
+            Omittable::Void => Err(SerError::custom(error_message)),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Nullable<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_present(deserializer, "Nullable").map(Into::into)
+    }
+}
+
+impl<T: Serialize> Serialize for Nullable<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_maybe().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod restricted_test {
+    use crate::{Nullable, Omittable};
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Dto {
+        #[serde(default, skip_serializing_if = "Omittable::is_void")]
+        omittable: Omittable<i32>,
+        nullable: Nullable<i32>,
+    }
+
+    #[test]
+    pub fn it_deserializes_allowed_states() {
+        let dto: Dto = serde_json::from_str(r#"{"nullable":null}"#).unwrap();
+        assert_eq!(
+            dto,
+            Dto {
+                omittable: Omittable::Void,
+                nullable: Nullable::None,
+            }
+        );
+        let dto: Dto = serde_json::from_str(r#"{"omittable":1,"nullable":2}"#).unwrap();
+        assert_eq!(
+            dto,
+            Dto {
+                omittable: Omittable::Some(1),
+                nullable: Nullable::Some(2),
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_rejects_null_omittable_values() {
+        let error = serde_json::from_str::<Dto>(r#"{"omittable":null,"nullable":1}"#).unwrap_err();
+        assert!(error.to_string().contains("must not be null"), "{}", error);
+    }
+
+    #[test]
+    pub fn it_rejects_missing_nullable_values() {
+        let error = serde_json::from_str::<Dto>(r#"{"omittable":1}"#).unwrap_err();
+        assert!(
+            error.to_string().contains("missing field `nullable`"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    pub fn it_serializes_restricted_values() {
+        let json = serde_json::to_string(&Dto {
+            omittable: Omittable::Void,
+            nullable: Nullable::None,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"nullable":null}"#);
+    }
+}
+
+#[cfg(test)]
+mod deny_test {
+    use crate::Maybe;
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Dto {
+        #[serde(
+            default,
+            skip_serializing_if = "Maybe::is_void",
+            with = "crate::serde::deny_null"
+        )]
+        name: Maybe<String>,
+        #[serde(with = "crate::serde::deny_void")]
+        email: Maybe<String>,
+    }
+
+    #[test]
+    pub fn it_accepts_allowed_states() {
+        let dto: Dto = serde_json::from_str(r#"{"email":null}"#).unwrap();
+        assert_eq!(
+            dto,
+            Dto {
+                name: Maybe::Void,
+                email: Maybe::None,
+            }
+        );
+    }
+
+    #[test]
+    pub fn it_denies_null() {
+        let error = serde_json::from_str::<Dto>(r#"{"name":null,"email":null}"#).unwrap_err();
+        assert_eq!(error.to_string(), "null is not allowed at line 1 column 12");
+        let dto = Dto {
+            name: Maybe::None,
+            email: Maybe::None,
+        };
+        assert!(serde_json::to_string(&dto).is_err());
+    }
+
+    #[test]
+    pub fn it_denies_void() {
+        let error = serde_json::from_str::<Dto>(r#"{"name":"a"}"#).unwrap_err();
+        assert!(
+            error.to_string().contains("missing field `email`"),
+            "{}",
+            error
+        );
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod test {
+    use crate::{Maybe, Omittable};
+    use ::serde::{Deserialize, Serialize};
+
+    #[maybe::serde_fields]
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Dto {
+        a: Maybe<i32>,
+        #[serde(rename = "bee")]
+        b: Maybe<String>,
+        #[serde(default)]
+        c: Maybe<bool>,
+        list: Vec<Maybe<i32>>,
+        omittable: Omittable<i32>,
+    }
+
+    #[maybe::serde_fields]
+    #[derive(Deserialize, Debug)]
+    struct Denying {
+        #[maybe(deny_null)]
+        name: Maybe<String>,
+        #[maybe(deny_void)]
+        email: Maybe<String>,
+    }
+
+    #[maybe::serde_fields(void = "skip")]
+    #[derive(Serialize)]
+    struct Skipping {
+        list: Vec<Maybe<i32>>,
+        array: [Maybe<i32>; 2],
+    }
+
+    #[maybe::serde_fields(void = "null")]
+    #[derive(Serialize)]
+    struct Nulling {
+        list: Vec<Maybe<i32>>,
+    }
+
+    #[maybe::serde_fields]
+    #[derive(Serialize)]
+    enum Event {
+        Changed { value: Maybe<i32> },
+    }
+
+    #[test]
+    pub fn it_skips_void_fields_without_annotations() {
+        let dto = Dto {
+            a: Maybe::Void,
+            b: Maybe::Some("b".into()),
+            c: Maybe::None,
+            list: vec![],
+            omittable: Omittable::Void,
+        };
+        let json = serde_json::to_string(&dto).expect("Couldn't serialize");
+        assert_eq!(json, r#"{"bee":"b","c":null,"list":[]}"#);
+        let parsed: Dto = serde_json::from_str(&json).expect("Couldn't deserialize");
+        assert_eq!(parsed, dto);
+    }
+
+    #[test]
+    pub fn it_skips_void_fields_in_enum_variants() {
+        let json = serde_json::to_string(&Event::Changed { value: Maybe::Void }).unwrap();
+        assert_eq!(json, r#"{"Changed":{}}"#);
+    }
+
+    #[test]
+    pub fn it_errors_on_void_in_sequences_by_default() {
+        let dto = Dto {
+            a: Maybe::Void,
+            b: Maybe::Void,
+            c: Maybe::Void,
+            list: vec![Maybe::Void],
+            omittable: Omittable::Void,
+        };
+        assert!(serde_json::to_string(&dto).is_err());
+    }
+
+    #[test]
+    pub fn it_names_denied_fields_in_errors() {
+        let error = serde_json::from_str::<Denying>(r#"{"name":null,"email":"a"}"#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("null is not allowed for field `name`"),
+            "{}",
+            error
+        );
+        let error = serde_json::from_str::<Denying>(r#"{"name":"a"}"#).unwrap_err();
+        assert!(
+            error.to_string().contains("missing field `email`"),
+            "{}",
+            error
+        );
+        let denying: Denying = serde_json::from_str(r#"{"email":null}"#).unwrap();
+        assert!(denying.name.is_void() && denying.email.is_none());
+    }
+
+    #[test]
+    pub fn it_applies_the_sequence_void_policy() {
+        let list = vec![Maybe::Some(1), Maybe::Void, Maybe::None];
+        let skipping = serde_json::to_string(&Skipping {
+            list: list.clone(),
+            array: [Maybe::Void, Maybe::Some(2)],
+        })
+        .unwrap();
+        assert_eq!(skipping, r#"{"list":[1,null],"array":[2]}"#);
+        let nulling = serde_json::to_string(&Nulling { list }).unwrap();
+        assert_eq!(nulling, r#"{"list":[1,null,null]}"#);
+    }
+
+    #[test]
+    pub fn it_serializes_void_as_null() {
+        let mut out = Vec::new();
+        super::void_as_null(
+            &Maybe::<i32>::Void,
+            &mut serde_json::Serializer::new(&mut out),
+        )
+        .unwrap();
+        assert_eq!(out, b"null");
+    }
+}
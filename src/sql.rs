@@ -0,0 +1,423 @@
+//! Partial `UPDATE` statements which only set the columns that aren't `Void`.
+//!
+//! The statements are database-agnostic: identifiers are quoted as chosen by [`Quoting`], `None`
+//! columns are set to a literal `NULL`, and every other value is bound as a parameter, leaving
+//! the driver to convert [`SqlValue`]s into its own types. Drivers may also bind their own
+//! parameter types, by implementing [`IntoParam`] for them.
+
+use crate::Maybe;
+use std::{error::Error, fmt};
+
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "rusqlite")]
+pub mod rusqlite;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+
+/// Returned by the drivers when asked to bind `Void`, which would write `NULL` to a column that
+/// should have been left alone.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct VoidParamError;
+
+impl fmt::Display for VoidParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`Maybe::Void` can't be bound as a parameter, leave its column out of the statement"
+        )
+    }
+}
+
+impl Error for VoidParamError {}
+
+/// A value bound as a statement parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+pub trait ToSqlValue {
+    fn to_sql_value(&self) -> SqlValue;
+}
+
+impl ToSqlValue for SqlValue {
+    fn to_sql_value(&self) -> SqlValue {
+        self.clone()
+    }
+}
+
+impl<T: ToSqlValue + ?Sized> ToSqlValue for &T {
+    fn to_sql_value(&self) -> SqlValue {
+        (**self).to_sql_value()
+    }
+}
+
+impl<T: ToSqlValue> ToSqlValue for Option<T> {
+    fn to_sql_value(&self) -> SqlValue {
+        match self {
+            Some(value) => value.to_sql_value(),
+            None => SqlValue::Null,
+        }
+    }
+}
+
+impl ToSqlValue for bool {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Bool(*self)
+    }
+}
+
+macro_rules! integer_sql_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ToSqlValue for $ty {
+                fn to_sql_value(&self) -> SqlValue {
+                    SqlValue::Integer((*self).into())
+                }
+            }
+        )*
+    };
+}
+
+integer_sql_value!(i8, i16, i32, i64, u8, u16, u32);
+
+impl ToSqlValue for f32 {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Real((*self).into())
+    }
+}
+
+impl ToSqlValue for f64 {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Real(*self)
+    }
+}
+
+impl ToSqlValue for str {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Text(self.to_string())
+    }
+}
+
+impl ToSqlValue for String {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Text(self.clone())
+    }
+}
+
+impl ToSqlValue for [u8] {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Blob(self.to_vec())
+    }
+}
+
+impl ToSqlValue for Vec<u8> {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Blob(self.clone())
+    }
+}
+
+/// Converts a value into the parameters of type `P` bound by an [`Update`].
+pub trait IntoParam<P> {
+    fn into_param(self) -> P;
+}
+
+impl<T: ToSqlValue> IntoParam<SqlValue> for T {
+    fn into_param(self) -> SqlValue {
+        self.to_sql_value()
+    }
+}
+
+/// How parameters are written in the generated SQL.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Placeholders {
+    /// `?`, as used by SQLite and MySQL.
+    #[default]
+    Question,
+    /// `$1`, `$2`, ..., as used by PostgreSQL.
+    Numbered,
+}
+
+/// How identifiers are quoted in the generated SQL.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Quoting {
+    /// `"name"`, as used by SQLite, PostgreSQL and MySQL in `ANSI_QUOTES` mode.
+    #[default]
+    Double,
+    /// `` `name` ``, as used by MySQL by default.
+    Backtick,
+}
+
+impl Quoting {
+    pub fn quote(self, identifier: &str) -> String {
+        match self {
+            Self::Double => format!("\"{}\"", identifier.replace('"', "\"\"")),
+            Self::Backtick => format!("`{}`", identifier.replace('`', "``")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement<P = SqlValue> {
+    pub sql: String,
+    pub params: Vec<P>,
+}
+
+/// Builds an `UPDATE` binding parameters of type `P`.
+#[derive(Debug, Clone)]
+pub struct Update<P = SqlValue> {
+    table: String,
+    key: Vec<(String, P)>,
+    columns: Vec<(String, Maybe<P>)>,
+    placeholders: Placeholders,
+    quoting: Quoting,
+}
+
+impl<P> Update<P> {
+    /// Starts an update of the rows of `table` where `column` equals `value`.
+    pub fn new(
+        table: impl Into<String>,
+        column: impl Into<String>,
+        value: impl IntoParam<P>,
+    ) -> Self {
+        Self::all_rows(table).key(column, value)
+    }
+
+    /// Starts an update of every row of `table`.
+    pub fn all_rows(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            key: Vec::new(),
+            columns: Vec::new(),
+            placeholders: Placeholders::default(),
+            quoting: Quoting::default(),
+        }
+    }
+
+    /// Also requires `column` to equal `value`, for composite keys.
+    pub fn key(mut self, column: impl Into<String>, value: impl IntoParam<P>) -> Self {
+        self.key.push((column.into(), value.into_param()));
+        self
+    }
+
+    pub fn set<V: IntoParam<P>>(mut self, column: impl Into<String>, value: Maybe<V>) -> Self {
+        self.columns
+            .push((column.into(), value.map(IntoParam::into_param)));
+        self
+    }
+
+    pub fn set_all<I, C, V>(self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = (C, Maybe<V>)>,
+        C: Into<String>,
+        V: IntoParam<P>,
+    {
+        columns
+            .into_iter()
+            .fold(self, |update, (column, value)| update.set(column, value))
+    }
+
+    pub fn placeholders(mut self, placeholders: Placeholders) -> Self {
+        self.placeholders = placeholders;
+        self
+    }
+
+    pub fn quoting(mut self, quoting: Quoting) -> Self {
+        self.quoting = quoting;
+        self
+    }
+
+    /// Returns `None` if every column is `Void`, as there is nothing to update.
+    pub fn build(&self) -> Option<Statement<P>>
+    where
+        P: Clone,
+    {
+        let mut params = Vec::new();
+        let mut bind = |value: &P| {
+            params.push(value.clone());
+            match self.placeholders {
+                Placeholders::Question => "?".to_string(),
+                Placeholders::Numbered => format!("${}", params.len()),
+            }
+        };
+
+        let assignments = self
+            .columns
+            .iter()
+            .filter_map(|(column, value)| {
+                let value = match value {
+                    Maybe::Void => return None,
+                    Maybe::None => "NULL".to_string(),
+                    Maybe::Some(value) => bind(value),
+                };
+                Some(format!("{} = {}", self.quoting.quote(column), value))
+            })
+            .collect::<Vec<_>>();
+        if assignments.is_empty() {
+            return None;
+        }
+
+        let mut sql = format!(
+            "UPDATE {} SET {}",
+            self.quoting.quote(&self.table),
+            assignments.join(", ")
+        );
+        let predicates = self
+            .key
+            .iter()
+            .map(|(column, value)| format!("{} = {}", self.quoting.quote(column), bind(value)))
+            .collect::<Vec<_>>();
+        if !predicates.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&predicates.join(" AND "));
+        }
+
+        Some(Statement { sql, params })
+    }
+}
+
+/// The table updated by the tests of this module and of the driver modules.
+#[cfg(test)]
+mod fixtures {
+    pub const USERS: &str = "
+        CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, bio TEXT);
+        INSERT INTO users VALUES (1, 'Ferris', 'Crab'), (2, 'Corro', NULL);";
+
+    pub fn connection() -> ::rusqlite::Connection {
+        let connection = ::rusqlite::Connection::open_in_memory().unwrap();
+        connection.execute_batch(USERS).unwrap();
+        connection
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fixtures::connection, *};
+    use ::rusqlite::{params_from_iter, types::Value, Connection};
+
+    fn execute(connection: &Connection, statement: Statement) -> usize {
+        let params = statement.params.into_iter().map(|value| match value {
+            SqlValue::Null => Value::Null,
+            SqlValue::Bool(value) => Value::Integer(value.into()),
+            SqlValue::Integer(value) => Value::Integer(value),
+            SqlValue::Real(value) => Value::Real(value),
+            SqlValue::Text(value) => Value::Text(value),
+            SqlValue::Blob(value) => Value::Blob(value),
+        });
+        connection
+            .execute(&statement.sql, params_from_iter(params))
+            .unwrap()
+    }
+
+    fn users(connection: &Connection) -> Vec<(i64, String, Option<String>)> {
+        let mut statement = connection
+            .prepare("SELECT id, name, bio FROM users ORDER BY id")
+            .unwrap();
+        statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    pub fn it_skips_void_columns() {
+        let statement = Update::new("users", "id", 1)
+            .set("name", Maybe::Some("Ferris the Crab"))
+            .set::<&str>("bio", Maybe::Void)
+            .build()
+            .unwrap();
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "users" SET "name" = ? WHERE "id" = ?"#
+        );
+        assert_eq!(
+            statement.params,
+            vec![
+                SqlValue::Text("Ferris the Crab".to_string()),
+                SqlValue::Integer(1)
+            ]
+        );
+    }
+
+    #[test]
+    pub fn it_sets_none_columns_to_null() {
+        let statement = Update::new("users", "id", 1)
+            .set_all([("name", Maybe::Void), ("bio", Maybe::<&str>::None)])
+            .placeholders(Placeholders::Numbered)
+            .build()
+            .unwrap();
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "users" SET "bio" = NULL WHERE "id" = $1"#
+        );
+        assert_eq!(statement.params, vec![SqlValue::Integer(1)]);
+    }
+
+    #[test]
+    pub fn it_builds_nothing_when_every_column_is_void() {
+        let update = Update::new("users", "id", 1)
+            .set::<&str>("name", Maybe::Void)
+            .set::<&str>("bio", Maybe::Void);
+        assert_eq!(update.build(), None);
+    }
+
+    #[test]
+    pub fn it_quotes_identifiers() {
+        let update = Update::all_rows(r#"odd "table`"#).set("column", Maybe::Some(1));
+        assert_eq!(
+            update.build().unwrap().sql,
+            r#"UPDATE "odd ""table`" SET "column" = ?"#
+        );
+        assert_eq!(
+            update.quoting(Quoting::Backtick).build().unwrap().sql,
+            r#"UPDATE `odd "table``` SET `column` = ?"#
+        );
+    }
+
+    #[test]
+    pub fn it_matches_every_key_column() {
+        let statement = Update::new("memberships", "user_id", 1)
+            .key("group_id", 2)
+            .set("role", Maybe::Some("admin"))
+            .placeholders(Placeholders::Numbered)
+            .build()
+            .unwrap();
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "memberships" SET "role" = $1 WHERE "user_id" = $2 AND "group_id" = $3"#
+        );
+    }
+
+    #[test]
+    pub fn it_updates_sqlite() {
+        let connection = connection();
+        let statement = Update::new("users", "id", 1)
+            .set("name", Maybe::Some("Ferris the Crab"))
+            .set::<&str>("bio", Maybe::None)
+            .build()
+            .unwrap();
+        assert_eq!(execute(&connection, statement), 1);
+
+        let statement = Update::new("users", "id", 2)
+            .set::<&str>("name", Maybe::Void)
+            .set("bio", Maybe::Some("Safe"))
+            .build()
+            .unwrap();
+        assert_eq!(execute(&connection, statement), 1);
+
+        assert_eq!(
+            users(&connection),
+            vec![
+                (1, "Ferris the Crab".to_string(), None),
+                (2, "Corro".to_string(), Some("Safe".to_string())),
+            ]
+        );
+    }
+}
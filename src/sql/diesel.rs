@@ -0,0 +1,162 @@
+//! Diesel changesets which skip `Void` fields.
+//!
+//! Diesel's own `AsChangeset` derive can't tell "leave alone" from "set to `NULL`", so structs
+//! of `Maybe` fields use `#[derive(maybe::AsChangeset)]` (with the `derive` feature) instead. It
+//! reads the same `#[diesel(table_name = ...)]`, `#[diesel(primary_key(...))]`,
+//! `#[diesel(column_name = ...)]` and `#[diesel(skip_update)]` attributes, leaving out primary
+//! keys, skipping `Void` fields and setting `None` fields to `NULL`. As `None` needs a nullable
+//! column, non-nullable columns use `Omittable` fields instead. Other fields are always set.
+
+use crate::{Maybe, Omittable};
+use ::diesel::{dsl::Eq, expression::AsExpression, sql_types::SqlType, ExpressionMethods};
+
+impl<T> Maybe<T> {
+    /// Assigns the value to `column`, for use in a changeset tuple, or nothing if `self` is
+    /// `Void`. `None` sets the column to `NULL`.
+    pub fn assign<C>(self, column: C) -> Option<Eq<C, Option<T>>>
+    where
+        C: ExpressionMethods,
+        C::SqlType: SqlType,
+        Option<T>: AsExpression<C::SqlType>,
+    {
+        self.into_double_option().map(|value| column.eq(value))
+    }
+}
+
+impl<T> Omittable<T> {
+    /// Assigns the value to `column`, for use in a changeset tuple, or nothing if `self` is
+    /// `Void`.
+    pub fn assign<C>(self, column: C) -> Option<Eq<C, T>>
+    where
+        C: ExpressionMethods,
+        C::SqlType: SqlType,
+        T: AsExpression<C::SqlType>,
+    {
+        self.into_option().map(|value| column.eq(value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sql::fixtures::USERS;
+    use ::diesel::{connection::SimpleConnection, prelude::*, sqlite::SqliteConnection};
+
+    ::diesel::table! {
+        users {
+            id -> Integer,
+            name -> Text,
+            bio -> Nullable<Text>,
+        }
+    }
+
+    fn connection() -> SqliteConnection {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        connection.batch_execute(USERS).unwrap();
+        connection
+    }
+
+    fn load(connection: &mut SqliteConnection) -> Vec<(i32, String, Option<String>)> {
+        users::table.order(users::id).load(connection).unwrap()
+    }
+
+    #[test]
+    pub fn it_assigns_maybe_in_changeset_tuples() {
+        let mut connection = connection();
+        ::diesel::update(users::table.find(1))
+            .set((
+                users::name.eq("Ferris the Crab"),
+                Maybe::<String>::None.assign(users::bio),
+            ))
+            .execute(&mut connection)
+            .unwrap();
+        ::diesel::update(users::table.find(2))
+            .set((
+                Omittable::Some("Corro").assign(users::name),
+                Maybe::<String>::Void.assign(users::bio),
+            ))
+            .execute(&mut connection)
+            .unwrap();
+
+        assert_eq!(
+            load(&mut connection),
+            vec![
+                (1, "Ferris the Crab".to_string(), None),
+                (2, "Corro".to_string(), None),
+            ]
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    pub fn it_derives_changesets_skipping_void() {
+        #[derive(crate::AsChangeset)]
+        #[diesel(table_name = users)]
+        struct UserChanges {
+            #[diesel(column_name = name)]
+            display_name: Omittable<String>,
+            bio: Maybe<String>,
+        }
+
+        #[derive(crate::AsChangeset)]
+        #[diesel(table_name = users)]
+        #[allow(dead_code)]
+        struct Rename {
+            id: i32,
+            name: String,
+            #[diesel(skip_update)]
+            bio: Maybe<String>,
+        }
+
+        #[derive(crate::AsChangeset)]
+        #[diesel(table_name = users, primary_key(name))]
+        #[allow(dead_code)]
+        struct BioByName {
+            name: String,
+            bio: Maybe<String>,
+        }
+
+        let mut connection = connection();
+        ::diesel::update(users::table.find(1))
+            .set(UserChanges {
+                display_name: Omittable::Void,
+                bio: Maybe::None,
+            })
+            .execute(&mut connection)
+            .unwrap();
+        ::diesel::update(users::table.find(2))
+            .set(UserChanges {
+                display_name: Omittable::Some("Corro the Unsafe".to_string()),
+                bio: Maybe::Void,
+            })
+            .execute(&mut connection)
+            .unwrap();
+        ::diesel::update(users::table.find(1))
+            .set(Rename {
+                id: 3,
+                name: "Ferris the Crab".to_string(),
+                bio: Maybe::Some("Crab".to_string()),
+            })
+            .execute(&mut connection)
+            .unwrap();
+        ::diesel::update(users::table.filter(users::name.eq("Corro the Unsafe")))
+            .set(BioByName {
+                name: "Corro".to_string(),
+                bio: Maybe::Some("Unsafe".to_string()),
+            })
+            .execute(&mut connection)
+            .unwrap();
+
+        assert_eq!(
+            load(&mut connection),
+            vec![
+                (1, "Ferris the Crab".to_string(), None),
+                (
+                    2,
+                    "Corro the Unsafe".to_string(),
+                    Some("Unsafe".to_string())
+                ),
+            ]
+        );
+    }
+}
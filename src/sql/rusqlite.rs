@@ -0,0 +1,217 @@
+//! Binding and reading `Maybe` values with rusqlite.
+//!
+//! As with sqlx, binding `Void` fails with [`VoidParamError`] rather than writing `NULL`.
+//! [`partial_update`] builds an `UPDATE` which leaves `Void` columns out instead. [`Update`] can
+//! also bind `&dyn ToSql` parameters directly.
+
+use super::{IntoParam, SqlValue, Statement, Update, VoidParamError};
+use crate::Maybe;
+use ::rusqlite::{
+    types::{FromSql, FromSqlResult, ToSqlOutput, Value, ValueRef},
+    Error, ToSql,
+};
+
+/// `NULL` reads as `None`; reading never produces `Void`.
+impl<T: FromSql> FromSql for Maybe<T> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Null => Ok(Self::None),
+            value => T::column_result(value).map(Self::Some),
+        }
+    }
+}
+
+impl<T: ToSql> ToSql for Maybe<T> {
+    fn to_sql(&self) -> ::rusqlite::Result<ToSqlOutput<'_>> {
+        match self {
+            Self::Void => Err(Error::ToSqlConversionFailure(Box::new(VoidParamError))),
+            Self::None => Ok(ToSqlOutput::Owned(Value::Null)),
+            Self::Some(value) => value.to_sql(),
+        }
+    }
+}
+
+/// Binds the parameters of statements built by [`Update`](super::Update).
+impl ToSql for SqlValue {
+    fn to_sql(&self) -> ::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Self::Null => ToSqlOutput::Owned(Value::Null),
+            Self::Bool(value) => ToSqlOutput::Owned(Value::Integer((*value).into())),
+            Self::Integer(value) => ToSqlOutput::Owned(Value::Integer(*value)),
+            Self::Real(value) => ToSqlOutput::Owned(Value::Real(*value)),
+            Self::Text(value) => ToSqlOutput::Borrowed(ValueRef::Text(value.as_bytes())),
+            Self::Blob(value) => ToSqlOutput::Borrowed(ValueRef::Blob(value)),
+        })
+    }
+}
+
+/// A parameter which may be left out of a statement, implemented by `Maybe`.
+pub trait MaybeParam {
+    /// Returns `None` if the parameter should be left out.
+    fn as_param(&self) -> Option<&dyn ToSql>;
+}
+
+impl<T: ToSql> MaybeParam for Maybe<T> {
+    fn as_param(&self) -> Option<&dyn ToSql> {
+        match self {
+            Self::Void => None,
+            value => Some(value),
+        }
+    }
+}
+
+impl<'a> IntoParam<&'a dyn ToSql> for &'a dyn ToSql {
+    fn into_param(self) -> &'a dyn ToSql {
+        self
+    }
+}
+
+/// Builds an `UPDATE` of `table` setting the named `columns` which aren't `Void`, for the rows
+/// matching every `key` column, or for every row if `key` is empty. Returns `None` if every
+/// column is `Void`.
+pub fn partial_update<'a>(
+    table: &str,
+    key: &[(&str, &'a dyn ToSql)],
+    columns: &[(&str, &'a dyn MaybeParam)],
+) -> Option<Statement<&'a dyn ToSql>> {
+    let update = key
+        .iter()
+        .fold(Update::all_rows(table), |update, (column, value)| {
+            update.key(*column, *value)
+        });
+    columns
+        .iter()
+        .fold(update, |update, (column, value)| {
+            update.set(*column, value.as_param().map_or(Maybe::Void, Maybe::Some))
+        })
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sql::fixtures::connection;
+    use ::rusqlite::{params, params_from_iter, Connection};
+
+    fn users(connection: &Connection) -> Vec<(i64, String, Maybe<String>)> {
+        let mut statement = connection
+            .prepare("SELECT id, name, bio FROM users ORDER BY id")
+            .unwrap();
+        statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    pub fn it_reads_null_as_none() {
+        assert_eq!(
+            users(&connection()),
+            vec![
+                (1, "Ferris".to_string(), Maybe::Some("Crab".to_string())),
+                (2, "Corro".to_string(), Maybe::None),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn it_binds_maybe() {
+        let connection = connection();
+        connection
+            .execute(
+                "UPDATE users SET bio = ?1 WHERE id = ?2",
+                params![Maybe::<String>::None, 1],
+            )
+            .unwrap();
+        connection
+            .execute(
+                "UPDATE users SET bio = ?1 WHERE id = ?2",
+                params![Maybe::Some("Unsafe"), 2],
+            )
+            .unwrap();
+        assert_eq!(
+            users(&connection),
+            vec![
+                (1, "Ferris".to_string(), Maybe::None),
+                (2, "Corro".to_string(), Maybe::Some("Unsafe".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn it_refuses_to_bind_void() {
+        let connection = connection();
+        let error = connection
+            .execute(
+                "UPDATE users SET bio = ?1 WHERE id = ?2",
+                params![Maybe::<String>::Void, 1],
+            )
+            .unwrap_err();
+        let Error::ToSqlConversionFailure(error) = error else {
+            panic!("expected a conversion failure, got {error:?}");
+        };
+        assert_eq!(error.downcast_ref(), Some(&VoidParamError));
+        assert_eq!(
+            error.to_string(),
+            "`Maybe::Void` can't be bound as a parameter, leave its column out of the statement"
+        );
+        assert_eq!(users(&connection)[0].2, Maybe::Some("Crab".to_string()));
+    }
+
+    #[test]
+    pub fn it_builds_partial_updates() {
+        let connection = connection();
+        let name = Maybe::<String>::Void;
+        let bio = Maybe::<String>::None;
+        let statement =
+            partial_update("users", &[("id", &1)], &[("name", &name), ("bio", &bio)]).unwrap();
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "users" SET "bio" = ? WHERE "id" = ?"#
+        );
+        assert_eq!(
+            connection
+                .execute(&statement.sql, statement.params.as_slice())
+                .unwrap(),
+            1
+        );
+
+        let name = Maybe::Some("Corro the Unsafe");
+        let statement =
+            partial_update("users", &[("id", &2)], &[("name", &name), ("bio", &bio)]).unwrap();
+        assert_eq!(
+            connection
+                .execute(&statement.sql, statement.params.as_slice())
+                .unwrap(),
+            1
+        );
+
+        assert_eq!(
+            users(&connection),
+            vec![
+                (1, "Ferris".to_string(), Maybe::None),
+                (2, "Corro the Unsafe".to_string(), Maybe::None),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn it_builds_nothing_when_every_column_is_void() {
+        let name = Maybe::<String>::Void;
+        assert!(partial_update("users", &[("id", &1)], &[("name", &name)]).is_none());
+    }
+
+    #[test]
+    pub fn it_binds_update_statements() {
+        let connection = connection();
+        let statement = Update::new("users", "id", 2)
+            .set("bio", Maybe::Some("Unsafe"))
+            .build()
+            .unwrap();
+        connection
+            .execute(&statement.sql, params_from_iter(&statement.params))
+            .unwrap();
+        assert_eq!(users(&connection)[1].2, Maybe::Some("Unsafe".to_string()));
+    }
+}
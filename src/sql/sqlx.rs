@@ -0,0 +1,129 @@
+//! Binding and decoding `Maybe` values with sqlx.
+//!
+//! `Void` can't be bound, as that would silently write `NULL` to a column which should have been
+//! left alone: encoding it fails with [`VoidParamError`], which sqlx reports when the query is
+//! executed.
+
+use super::VoidParamError;
+use crate::Maybe;
+use ::sqlx::{
+    encode::IsNull, error::BoxDynError, Database, Decode, Encode, Type, TypeInfo, ValueRef,
+};
+
+impl<T: Type<DB>, DB: Database> Type<DB> for Maybe<T> {
+    fn type_info() -> DB::TypeInfo {
+        T::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        ty.is_null() || T::compatible(ty)
+    }
+}
+
+impl<'q, T, DB> Encode<'q, DB> for Maybe<T>
+where
+    T: Encode<'q, DB> + Type<DB>,
+    DB: Database,
+{
+    fn encode(self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        match self {
+            Self::Void => Err(Box::new(VoidParamError)),
+            Self::None => Ok(IsNull::Yes),
+            Self::Some(value) => value.encode(buf),
+        }
+    }
+
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        match self {
+            Self::Void => Err(Box::new(VoidParamError)),
+            Self::None => Ok(IsNull::Yes),
+            Self::Some(value) => value.encode_by_ref(buf),
+        }
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        match self {
+            Self::Some(value) => value.produces(),
+            _ => Some(T::type_info()),
+        }
+    }
+
+    fn size_hint(&self) -> usize {
+        match self {
+            Self::Some(value) => value.size_hint(),
+            _ => 0,
+        }
+    }
+}
+
+/// `NULL` decodes as `None`; decoding never produces `Void`.
+impl<'r, T: Decode<'r, DB>, DB: Database> Decode<'r, DB> for Maybe<T> {
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_null() {
+            Ok(Self::None)
+        } else {
+            T::decode(value).map(Self::Some)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::sqlx::{sqlite::SqlitePool, Error};
+
+    async fn pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        ::sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY, bio TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    pub async fn it_binds_and_decodes_maybe() {
+        let pool = pool().await;
+        ::sqlx::query("INSERT INTO users VALUES (1, ?), (2, ?)")
+            .bind(Maybe::Some("Crab"))
+            .bind(Maybe::<&str>::None)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let bios: Vec<(i64, Maybe<String>)> =
+            ::sqlx::query_as("SELECT id, bio FROM users ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            bios,
+            vec![(1, Maybe::Some("Crab".to_string())), (2, Maybe::None)]
+        );
+    }
+
+    #[tokio::test]
+    pub async fn it_refuses_to_bind_void() {
+        let pool = pool().await;
+        let mut query = ::sqlx::query("INSERT INTO users VALUES (1, ?)");
+        let error = query.try_bind(Maybe::<&str>::Void).unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&VoidParamError));
+        assert_eq!(
+            error.to_string(),
+            "`Maybe::Void` can't be bound as a parameter, leave its column out of the statement"
+        );
+
+        let error = ::sqlx::query("INSERT INTO users VALUES (1, ?)")
+            .bind(Maybe::<&str>::Void)
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Encode(_)));
+
+        let count: i64 = ::sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}